@@ -0,0 +1,544 @@
+//! Audit of the whole network in a save file.
+//!
+//! Links are listed between the DSN and each vessel, then between every pair
+//! of vessels. Distances are upper bounds from the orbits of their bodies and
+//! their own apoapses. DSN is at the home body.
+
+use std::path::Path;
+
+use anyhow::Result;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use serde::Serialize;
+
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::bodies::{Body, System};
+use crate::catalog::Catalog;
+use crate::cli::{load_system, parse_modifiers};
+use crate::endpoint::{is_relay, EndpointBuilder, Modifiers};
+use crate::render::format_strength;
+use crate::save::{Save, Vessel};
+use crate::signal::strength_at;
+use crate::DEFAULT_TO;
+
+pub const NAME: &str = "audit";
+
+const SCHEMA_VERSION: u32 = 1;
+
+/// Vessel types which are never a part of the network.
+const EXCLUDED_TYPES: &[&str] = &["SpaceObject", "Flag"];
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME)
+        .about("Audit links between all vessels in a save")
+        .arg(
+            Arg::with_name("save")
+                .long("save")
+                .takes_value(true)
+                .required(true)
+                .value_name("PATH")
+                .help("KSP save file (persistent.sfs)"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["markdown", "json"])
+                .default_value("markdown")
+                .help("Output format"),
+        )
+        .arg(
+            Arg::with_name("include-debris")
+                .long("include-debris")
+                .help("Include debris with antennas, which are skipped by default"),
+        )
+}
+
+struct AuditVessel<'a> {
+    name: String,
+    body: Option<&'a Body>,
+    apoapsis: f64,
+    endpoint: Endpoint,
+    /// Endpoint with relay antennas only, if any.
+    relay: Option<Endpoint>,
+}
+
+pub fn audit(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let modifiers = parse_modifiers(matches)?;
+    let save = Save::load(Path::new(matches.value_of("save").unwrap()))?;
+    let system = load_system(matches)?;
+    let report = audit_report(
+        &save,
+        antennas,
+        modifiers,
+        &system,
+        matches.is_present("include-debris"),
+    )?;
+
+    match matches.value_of("format") {
+        Some("json") => println!("{}", serde_json::to_string_pretty(&report)?),
+        _ => print_markdown(&report),
+    }
+
+    Ok(())
+}
+
+fn audit_report(
+    save: &Save,
+    antennas: &Catalog,
+    modifiers: Modifiers,
+    system: &System,
+    include_debris: bool,
+) -> Result<AuditReport> {
+    let home = system.home();
+    let builder = EndpointBuilder::new(antennas).modifiers(modifiers);
+
+    let dsn_name = dsn_antenna(save.tracking_station_level);
+    let dsn = builder.build(std::iter::once(dsn_name), DEFAULT_TO)?;
+
+    let mut nodes = Vec::new();
+    for vessel in &save.vessels {
+        if !is_audited(vessel.vessel_type.as_deref(), include_debris) {
+            continue;
+        }
+        if let Some(v) = audit_vessel(vessel, antennas, &builder, system)? {
+            nodes.push(v);
+        }
+    }
+
+    let to_dsn: Vec<Option<f64>> = nodes
+        .iter()
+        .map(|n| n.body.map(|b| system.max_distance(b, home) + n.apoapsis))
+        .collect();
+
+    let mut links = Vec::new();
+    for (node, &distance) in nodes.iter().zip(&to_dsn) {
+        links.push(JsonLink::new(
+            dsn_name,
+            &dsn,
+            &node.name,
+            &node.endpoint,
+            distance,
+        ));
+    }
+    for (i, a) in nodes.iter().enumerate() {
+        for b in &nodes[i + 1..] {
+            let distance = max_distance(system, a, b);
+            links.push(JsonLink::new(
+                &a.name,
+                &a.endpoint,
+                &b.name,
+                &b.endpoint,
+                distance,
+            ));
+        }
+    }
+
+    let mut vessels = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let mut reaches = reaches_relay(&node.endpoint, &dsn, to_dsn[i]);
+        for (j, other) in nodes.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if let Some(relay) = &other.relay {
+                reaches |= reaches_relay(&node.endpoint, relay, max_distance(system, node, other));
+            }
+        }
+
+        vessels.push(JsonVessel {
+            name: node.name.clone(),
+            body: node.body.map(|b| b.name.clone()),
+            power: node.endpoint.power(),
+            relay: node.relay.is_some(),
+            reaches_relay: reaches,
+        });
+    }
+
+    Ok(AuditReport {
+        version: SCHEMA_VERSION,
+        dsn: dsn_name.to_owned(),
+        vessels,
+        links,
+    })
+}
+
+fn audit_vessel<'a>(
+    vessel: &Vessel,
+    antennas: &Catalog,
+    builder: &EndpointBuilder<'_>,
+    system: &'a System,
+) -> Result<Option<AuditVessel<'a>>> {
+    let part_antennas = vessel.antennas(antennas);
+    for (part, module) in &part_antennas.unknown {
+        eprintln!(
+            "Warning: unknown antenna part on '{}': {} ({})",
+            vessel.name, part, module
+        );
+    }
+    if part_antennas.counts.is_empty() {
+        return Ok(None);
+    }
+
+    let specs = part_antennas.specs();
+    let endpoint = builder.build(specs.iter().map(String::as_str), DEFAULT_TO)?;
+
+    let relay_specs: Vec<&str> = part_antennas
+        .counts
+        .iter()
+        .zip(specs.iter())
+        .filter(|((n, _), _)| {
+            antennas
+                .get(n)
+                .map(|a| is_relay(antennas, a))
+                .unwrap_or(false)
+        })
+        .map(|(_, s)| s.as_str())
+        .collect();
+    let relay = if relay_specs.is_empty() {
+        None
+    } else {
+        Some(builder.build(relay_specs.into_iter(), DEFAULT_TO)?)
+    };
+
+    Ok(Some(AuditVessel {
+        name: vessel.name.clone(),
+        body: vessel.orbit.and_then(|o| system.by_index(o.body)),
+        apoapsis: vessel.orbit.map(|o| o.apoapsis()).unwrap_or(0.0),
+        endpoint,
+        relay,
+    }))
+}
+
+fn is_audited(vessel_type: Option<&str>, include_debris: bool) -> bool {
+    match vessel_type {
+        Some("Debris") => include_debris,
+        Some(t) => !EXCLUDED_TYPES.contains(&t),
+        None => true,
+    }
+}
+
+/// Name of the DSN antenna for the tracking station level. Sandbox has no level, and it is the max.
+fn dsn_antenna(level: Option<f64>) -> &'static str {
+    match level {
+        Some(l) if l < 0.25 => "DSN Lv.1",
+        Some(l) if l < 0.75 => "DSN Lv.2",
+        _ => "DSN Lv.3",
+    }
+}
+
+fn max_distance(system: &System, a: &AuditVessel, b: &AuditVessel) -> Option<f64> {
+    match (a.body, b.body) {
+        (Some(ba), Some(bb)) => Some(system.max_distance(ba, bb) + a.apoapsis + b.apoapsis),
+        _ => None,
+    }
+}
+
+fn reaches_relay(endpoint: &Endpoint, relay: &Endpoint, distance: Option<f64>) -> bool {
+    let range = endpoint.range_to(relay).max_distance();
+    distance.and_then(|d| strength_at(range, d)).is_some()
+}
+
+#[derive(Debug, Serialize)]
+struct AuditReport {
+    version: u32,
+    dsn: String,
+    vessels: Vec<JsonVessel>,
+    links: Vec<JsonLink>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonVessel {
+    name: String,
+    body: Option<String>,
+    power: f64,
+    relay: bool,
+    reaches_relay: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonLink {
+    from: String,
+    to: String,
+    range: f64,
+    max_distance: Option<f64>,
+    strength: Option<f64>,
+}
+
+impl JsonLink {
+    fn new(
+        from: &str,
+        from_endpoint: &Endpoint,
+        to: &str,
+        to_endpoint: &Endpoint,
+        distance: Option<f64>,
+    ) -> Self {
+        let range = from_endpoint.range_to(to_endpoint).max_distance();
+        JsonLink {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            range,
+            max_distance: distance,
+            strength: distance.and_then(|d| strength_at(range, d)),
+        }
+    }
+}
+
+fn print_markdown(report: &AuditReport) {
+    println!();
+    println!(" DSN: {}", report.dsn);
+    println!();
+
+    println!(" |         Vessel            |   Body   |  Power   | Relay | Reaches relay |");
+    println!(" |:--------------------------|:---------|---------:|:-----:|:-------------:|");
+    for v in &report.vessels {
+        println!(
+            " | {:<25} | {:<8} | {:>8} | {:^5} | {:^13} |",
+            v.name,
+            v.body.as_deref().unwrap_or("?"),
+            MetricPrefix(v.power).to_string(),
+            if v.relay { "yes" } else { "" },
+            if v.reaches_relay { "yes" } else { "NO" },
+        );
+    }
+    println!();
+
+    println!(" |           From            |            To             |  Range   | Max dist | Strength |");
+    println!(" |:--------------------------|:--------------------------|---------:|---------:|---------:|");
+    for l in &report.links {
+        println!(
+            " | {:<25} | {:<25} | {:>8} | {:>8} | {:>8} |",
+            l.from,
+            l.to,
+            format!("{}m", MetricPrefix(l.range)),
+            l.max_distance
+                .map(|d| format!("{}m", MetricPrefix(d)))
+                .unwrap_or_else(|| "?".to_owned()),
+            format_strength(l.strength),
+        );
+    }
+    println!();
+
+    let unreachable: Vec<&str> = report
+        .vessels
+        .iter()
+        .filter(|v| !v.reaches_relay)
+        .map(|v| v.name.as_str())
+        .collect();
+    if !unreachable.is_empty() {
+        println!(
+            " Vessels without a relay in range: {}",
+            unreachable.join(", ")
+        );
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAVE: &str = "
+GAME
+{
+    SCENARIO
+    {
+        name = ScenarioUpgradeableFacilities
+        SpaceCenter/TrackingStation
+        {
+            lvl = 0.5
+        }
+    }
+    FLIGHTSTATE
+    {
+        VESSEL
+        {
+            name = Relay One
+            type = Relay
+            ORBIT
+            {
+                SMA = 3000000
+                ECC = 0
+                REF = 1
+            }
+            PART
+            {
+                name = RelayAntenna100
+            }
+        }
+        VESSEL
+        {
+            name = Duna Probe
+            type = Probe
+            ORBIT
+            {
+                SMA = 1000000
+                ECC = 0.1
+                REF = 6
+            }
+            PART
+            {
+                name = longAntenna
+            }
+        }
+        VESSEL
+        {
+            name = Mun Lander
+            type = Lander
+            ORBIT
+            {
+                SMA = 250000
+                ECC = 0
+                REF = 2
+            }
+            PART
+            {
+                name = SurfAntenna
+            }
+        }
+        VESSEL
+        {
+            name = Pod
+            type = Ship
+            ORBIT
+            {
+                SMA = 700000
+                ECC = 0
+                REF = 1
+            }
+            PART
+            {
+                name = mk1pod.v2
+                MODULE
+                {
+                    name = ModuleCommand
+                }
+                MODULE
+                {
+                    name = ModuleDataTransmitter
+                }
+            }
+        }
+        VESSEL
+        {
+            name = Old Stage
+            type = Debris
+            ORBIT
+            {
+                SMA = 800000
+                ECC = 0
+                REF = 1
+            }
+            PART
+            {
+                name = longAntenna
+            }
+        }
+        VESSEL
+        {
+            name = Rock
+            type = SpaceObject
+        }
+    }
+}
+";
+
+    fn report(include_debris: bool) -> AuditReport {
+        let save = Save::parse(SAVE).unwrap();
+        audit_report(
+            &save,
+            &Catalog::new(),
+            Modifiers::default(),
+            &System::stock(),
+            include_debris,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_save() {
+        let save = Save::parse(SAVE).unwrap();
+        assert_eq!(save.tracking_station_level, Some(0.5));
+        assert_eq!(save.vessels.len(), 6);
+
+        let relay = &save.vessels[0];
+        assert_eq!(relay.name, "Relay One");
+        assert_eq!(relay.vessel_type.as_deref(), Some("Relay"));
+        let orbit = relay.orbit.unwrap();
+        assert_eq!((orbit.body, orbit.sma, orbit.eccentricity), (1, 3e6, 0.0));
+        assert!(save.vessels[5].orbit.is_none());
+
+        assert!(Save::parse("FOO\n{\n}\n").is_err());
+    }
+
+    #[test]
+    fn selects_dsn_level() {
+        assert_eq!(dsn_antenna(None), "DSN Lv.3");
+        assert_eq!(dsn_antenna(Some(0.0)), "DSN Lv.1");
+        assert_eq!(dsn_antenna(Some(0.5)), "DSN Lv.2");
+        assert_eq!(dsn_antenna(Some(1.0)), "DSN Lv.3");
+        assert_eq!(report(false).dsn, "DSN Lv.2");
+    }
+
+    #[test]
+    fn audits_vessels() {
+        let r = report(false);
+        let names: Vec<&str> = r.vessels.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["Relay One", "Duna Probe", "Mun Lander", "Pod"]);
+
+        let relays: Vec<bool> = r.vessels.iter().map(|v| v.relay).collect();
+        assert_eq!(relays, vec![true, false, false, false]);
+        let reaches: Vec<bool> = r.vessels.iter().map(|v| v.reaches_relay).collect();
+        assert_eq!(reaches, vec![true, false, true, true]);
+        assert_eq!(r.vessels[1].body.as_deref(), Some("Duna"));
+
+        assert_eq!(report(true).vessels.len(), 5);
+    }
+
+    #[test]
+    fn links_dsn_and_every_pair() {
+        let r = report(false);
+        assert_eq!(r.links.len(), 4 + 6);
+
+        let dsn: Vec<&str> = r.links[..4].iter().map(|l| l.to.as_str()).collect();
+        assert_eq!(dsn, vec!["Relay One", "Duna Probe", "Mun Lander", "Pod"]);
+        assert!(r.links[..4].iter().all(|l| l.from == "DSN Lv.2"));
+        assert_eq!(r.links[0].max_distance, Some(3e6));
+        assert!(r.links[0].strength.is_some());
+        assert_eq!(r.links[1].strength, None);
+
+        // Max distance between Kerbin and the Mun, plus both apoapses.
+        let link = r
+            .links
+            .iter()
+            .find(|l| l.from == "Relay One" && l.to == "Mun Lander")
+            .unwrap();
+        assert_eq!(link.max_distance, Some(12e6 + 3e6 + 250e3));
+    }
+
+    #[test]
+    fn reaches_relay_in_range() {
+        let catalog = Catalog::new();
+        let builder = EndpointBuilder::new(&catalog);
+        let probe = builder.build(vec!["Communotron 16"], DEFAULT_TO).unwrap();
+        let relay = builder.build(vec!["RA-100"], DEFAULT_TO).unwrap();
+        let range = probe.range_to(&relay).max_distance();
+
+        assert!(reaches_relay(&probe, &relay, Some(0.5 * range)));
+        assert!(reaches_relay(&probe, &relay, Some(range)));
+        assert!(!reaches_relay(&probe, &relay, Some(2.0 * range)));
+        assert!(!reaches_relay(&probe, &relay, None));
+    }
+
+    #[test]
+    fn filters_vessel_types() {
+        assert!(is_audited(Some("Probe"), false));
+        assert!(is_audited(Some("Relay"), false));
+        assert!(is_audited(None, false));
+        assert!(!is_audited(Some("Debris"), false));
+        assert!(is_audited(Some("Debris"), true));
+        assert!(!is_audited(Some("SpaceObject"), true));
+        assert!(!is_audited(Some("Flag"), true));
+    }
+}
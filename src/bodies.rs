@@ -0,0 +1,344 @@
+//! Celestial bodies of the planetary system.
+//!
+//! The stock system is built in. Planet packs and rescales are read from a
+//! TOML or JSON file with a list of `body` entries, in the order of their
+//! index in saves:
+//!
+//! ```toml
+//! home = "Kerbin"
+//!
+//! [[body]]
+//! name = "Kerbol"
+//! radius = 261600000
+//!
+//! [[body]]
+//! name = "Kerbin"
+//! parent = "Kerbol"
+//! sma = 13599840256
+//! eccentricity = 0.0
+//! radius = 600000
+//! soi = 84159286
+//! mu = 3.5316e12
+//! lan = 0.0
+//! arg_periapsis = 0.0
+//! mean_anomaly = 3.14
+//! rotation_period = 21549.425
+//! ```
+//!
+//! `home` is the body of the DSN and defaults to `Kerbin`. The star has no
+//! `parent`, `sma` and `soi`. `mu`, `lan`, `arg_periapsis` and `mean_anomaly`
+//! are used only by `timeline`, and inclinations are ignored. `rotation_period`
+//! is used only by `coverage`.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Body {
+    pub name: String,
+    /// Body this orbits, or `None` for the star.
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Semi-major axis in meters.
+    #[serde(default)]
+    pub sma: f64,
+    #[serde(default)]
+    pub eccentricity: f64,
+    /// Radius in meters.
+    pub radius: f64,
+    /// Radius of the sphere of influence in meters.
+    #[serde(default = "infinity")]
+    pub soi: f64,
+    /// Gravitational parameter in m³/s², needed to propagate orbits around the body.
+    #[serde(default)]
+    pub mu: f64,
+    /// Longitude of the ascending node in degrees.
+    #[serde(default)]
+    pub lan: f64,
+    /// Argument of periapsis in degrees.
+    #[serde(default)]
+    pub arg_periapsis: f64,
+    /// Mean anomaly at the epoch (Y1 D1) in radians.
+    #[serde(default)]
+    pub mean_anomaly: f64,
+    /// Sidereal rotation period in seconds, or 0 if the body does not rotate.
+    #[serde(default)]
+    pub rotation_period: f64,
+}
+
+fn infinity() -> f64 {
+    f64::INFINITY
+}
+
+impl Body {
+    pub fn periapsis(&self) -> f64 {
+        self.sma * (1.0 - self.eccentricity)
+    }
+
+    pub fn apoapsis(&self) -> f64 {
+        self.sma * (1.0 + self.eccentricity)
+    }
+}
+
+pub struct System {
+    bodies: Vec<Body>,
+    home: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SystemFile {
+    home: Option<String>,
+    #[serde(default)]
+    body: Vec<Body>,
+}
+
+/// Stock bodies in the order of their index in saves, as (name, parent, semi-major axis,
+/// eccentricity, radius, SOI, gravitational parameter, longitude of the ascending node,
+/// argument of periapsis, mean anomaly at epoch, rotation period).
+type StockBody = (
+    &'static str,
+    Option<&'static str>,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+    f64,
+);
+
+// Mean anomalies of 3.14 are the values of the game, not approximations of pi.
+#[allow(clippy::approx_constant)]
+#[rustfmt::skip]
+const STOCK: &[StockBody] = &[
+    ("Kerbol", None, 0.0, 0.0, 261_600_000.0, f64::INFINITY, 1.172_332_8e18, 0.0, 0.0, 0.0, 432_000.0),
+    ("Kerbin", Some("Kerbol"), 13_599_840_256.0, 0.0, 600_000.0, 84_159_286.0, 3.531_6e12, 0.0, 0.0, 3.14, 21_549.425),
+    ("Mun", Some("Kerbin"), 12_000_000.0, 0.0, 200_000.0, 2_429_559.1, 6.513_839_8e10, 0.0, 0.0, 1.7, 138_984.38),
+    ("Minmus", Some("Kerbin"), 47_000_000.0, 0.0, 60_000.0, 2_247_428.4, 1.765_8e9, 78.0, 38.0, 0.9, 40_400.0),
+    ("Moho", Some("Kerbol"), 5_263_138_304.0, 0.2, 250_000.0, 9_646_663.0, 1.686_093_8e11, 70.0, 15.0, 3.14, 1_210_000.0),
+    ("Eve", Some("Kerbol"), 9_832_684_544.0, 0.01, 700_000.0, 85_109_365.0, 8.171_730_2e12, 15.0, 0.0, 3.14, 80_500.0),
+    ("Duna", Some("Kerbol"), 20_726_155_264.0, 0.051, 320_000.0, 47_921_949.0, 3.013_632_1e11, 135.5, 0.0, 3.14, 65_517.859),
+    ("Ike", Some("Duna"), 3_200_000.0, 0.03, 130_000.0, 1_049_598.9, 1.856_836_9e10, 0.0, 0.0, 1.7, 65_517.862),
+    ("Jool", Some("Kerbol"), 68_773_560_320.0, 0.05, 6_000_000.0, 2_455_985_200.0, 2.825_28e14, 52.0, 0.0, 0.1, 36_000.0),
+    ("Laythe", Some("Jool"), 27_184_000.0, 0.0, 500_000.0, 3_723_645.8, 1.962e12, 0.0, 0.0, 3.14, 52_980.879),
+    ("Vall", Some("Jool"), 43_152_000.0, 0.0, 300_000.0, 2_406_401.4, 2.074_815e11, 0.0, 0.0, 0.9, 105_962.09),
+    ("Bop", Some("Jool"), 128_500_000.0, 0.235, 65_000.0, 1_221_060.9, 2.486_834_9e9, 10.0, 25.0, 0.9, 544_507.43),
+    ("Tylo", Some("Jool"), 68_500_000.0, 0.0, 600_000.0, 10_856_518.0, 2.825_28e12, 0.0, 0.0, 3.14, 211_926.36),
+    ("Gilly", Some("Eve"), 31_500_000.0, 0.55, 13_000.0, 126_123.27, 8.289_45e6, 80.0, 10.0, 0.9, 28_255.0),
+    ("Pol", Some("Jool"), 179_890_000.0, 0.171, 44_000.0, 1_042_138.9, 7.217_020_8e8, 2.0, 15.0, 0.9, 901_902.62),
+    ("Dres", Some("Kerbol"), 40_839_348_203.0, 0.145, 138_000.0, 32_832_840.0, 2.148_448_9e10, 280.0, 90.0, 3.14, 34_800.0),
+    ("Eeloo", Some("Kerbol"), 90_118_820_000.0, 0.26, 210_000.0, 119_082_940.0, 7.441_081_5e10, 50.0, 260.0, 3.14, 19_460.0),
+];
+
+pub const HOME: &str = "Kerbin";
+
+impl System {
+    pub fn stock() -> System {
+        let bodies = STOCK
+            .iter()
+            .map(
+                |&(
+                    name,
+                    parent,
+                    sma,
+                    eccentricity,
+                    radius,
+                    soi,
+                    mu,
+                    lan,
+                    arg_periapsis,
+                    mean_anomaly,
+                    rotation_period,
+                )| {
+                    Body {
+                        name: name.to_owned(),
+                        parent: parent.map(str::to_owned),
+                        sma,
+                        eccentricity,
+                        radius,
+                        soi,
+                        mu,
+                        lan,
+                        arg_periapsis,
+                        mean_anomaly,
+                        rotation_period,
+                    }
+                },
+            )
+            .collect();
+
+        System {
+            bodies,
+            home: HOME.to_owned(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<System> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?;
+
+        let is_json = path
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        let file: SystemFile = if is_json {
+            serde_json::from_str(&source)
+                .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?
+        } else {
+            toml::from_str(&source).map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?
+        };
+
+        let system = System {
+            bodies: file.body,
+            home: file.home.unwrap_or_else(|| HOME.to_owned()),
+        };
+        system
+            .validate()
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?;
+        Ok(system)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let stars = self.bodies.iter().filter(|b| b.parent.is_none()).count();
+        if stars != 1 {
+            return Err(Error::msg(format!(
+                "there should be exactly one body without parent, but {}",
+                stars
+            )));
+        }
+
+        for (i, b) in self.bodies.iter().enumerate() {
+            if self.bodies[..i]
+                .iter()
+                .any(|o| o.name.eq_ignore_ascii_case(&b.name))
+            {
+                return Err(Error::msg(format!("body '{}' is defined twice", b.name)));
+            }
+            if let Some(p) = &b.parent {
+                if self.get(p).is_none() {
+                    return Err(Error::msg(format!(
+                        "parent '{}' of '{}' is not defined",
+                        p, b.name
+                    )));
+                }
+                if b.sma <= 0.0 || !b.sma.is_finite() {
+                    return Err(Error::msg(format!(
+                        "sma of '{}' should be positive",
+                        b.name
+                    )));
+                }
+            }
+            if !(0.0..1.0).contains(&b.eccentricity) {
+                return Err(Error::msg(format!(
+                    "eccentricity of '{}' should be in 0 <= e < 1",
+                    b.name
+                )));
+            }
+            if b.radius <= 0.0 || !b.radius.is_finite() {
+                return Err(Error::msg(format!(
+                    "radius of '{}' should be positive",
+                    b.name
+                )));
+            }
+            if b.soi <= b.radius {
+                return Err(Error::msg(format!(
+                    "soi of '{}' should be larger than its radius",
+                    b.name
+                )));
+            }
+        }
+
+        for b in &self.bodies {
+            if self.ancestors(b).last().map(|r| r.parent.is_some()) == Some(true) {
+                return Err(Error::msg(format!("parents of '{}' make a loop", b.name)));
+            }
+        }
+
+        if self.get(&self.home).is_none() {
+            return Err(Error::msg(format!(
+                "home body '{}' is not defined",
+                self.home
+            )));
+        }
+        Ok(())
+    }
+
+    /// Scales orbits, radii and SOIs by `factor`, like rescale mods do.
+    /// Masses are scaled to keep the surface gravity.
+    pub fn rescale(&mut self, factor: f64) {
+        for b in &mut self.bodies {
+            b.sma *= factor;
+            b.radius *= factor;
+            b.soi *= factor;
+            b.mu *= factor * factor;
+        }
+    }
+
+    /// Body of the DSN.
+    pub fn home(&self) -> &Body {
+        self.get(&self.home).expect("Home body not exists")
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Body> {
+        self.bodies.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Body> {
+        self.bodies
+            .iter()
+            .find(|b| b.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Body by its index in saves.
+    pub fn by_index(&self, index: usize) -> Option<&Body> {
+        self.bodies.get(index)
+    }
+
+    /// Upper bound of the distance between the centers of two bodies.
+    ///
+    /// Sums the apoapses on the paths to their closest common ancestor.
+    pub fn max_distance(&self, a: &Body, b: &Body) -> f64 {
+        let path_a = self.ancestors(a);
+        let path_b = self.ancestors(b);
+
+        let common = path_a
+            .iter()
+            .position(|x| path_b.iter().any(|y| y.name == x.name));
+
+        let sum = |path: &[&Body], end: &str| -> f64 {
+            path.iter()
+                .take_while(|x| x.name != end)
+                .map(|x| x.apoapsis())
+                .sum()
+        };
+
+        match common {
+            Some(i) => {
+                let lca = &path_a[i].name;
+                sum(&path_a, lca) + sum(&path_b, lca)
+            }
+            None => sum(&path_a, "") + sum(&path_b, ""),
+        }
+    }
+
+    /// `body` and its ancestors, from `body` to the star.
+    pub fn ancestors<'a>(&'a self, body: &'a Body) -> Vec<&'a Body> {
+        let mut path = vec![body];
+        let mut current = body;
+        while let Some(parent) = current.parent.as_ref().and_then(|p| self.get(p)) {
+            if path.len() > self.bodies.len() {
+                break;
+            }
+            path.push(parent);
+            current = parent;
+        }
+        path
+    }
+}
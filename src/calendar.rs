@@ -0,0 +1,147 @@
+//! Kerbin calendar: 6 hour days and 426 day years, starting at Y1 D1.
+
+use anyhow::{Error, Result};
+
+pub const HOUR: f64 = 3600.0;
+pub const DAY: f64 = 6.0 * HOUR;
+pub const YEAR: f64 = 426.0 * DAY;
+
+/// Parses a date like `Y2 D100` or `y2d100` into seconds since Y1 D1.
+/// Plain numbers are seconds, and should be finite and non-negative.
+pub fn parse_date(s: &str) -> Result<f64> {
+    let invalid = || Error::msg(format!("invalid date '{}', expected like 'Y2 D100'", s));
+
+    let t = s.trim();
+    if let Ok(v) = t.parse::<f64>() {
+        if !v.is_finite() || v < 0.0 {
+            return Err(invalid());
+        }
+        return Ok(v);
+    }
+
+    let t = t.to_ascii_lowercase();
+    let rest = t.strip_prefix('y').ok_or_else(invalid)?;
+    let d = rest.find('d').ok_or_else(invalid)?;
+    let year: u32 = rest[..d].trim().parse().map_err(|_| invalid())?;
+    let day: u32 = rest[d + 1..].trim().parse().map_err(|_| invalid())?;
+    if year < 1 || day < 1 || day > 426 {
+        return Err(invalid());
+    }
+
+    Ok(f64::from(year - 1) * YEAR + f64::from(day - 1) * DAY)
+}
+
+/// Parses a duration like `6h`, `10d` or `2y`. Plain numbers are seconds.
+pub fn parse_duration(s: &str) -> Result<f64> {
+    let t = s.trim();
+    let (num, unit) = match t.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&t[..i], Some(c)),
+        _ => (t, None),
+    };
+    let unit = match unit {
+        None | Some('s') => 1.0,
+        Some('m') => 60.0,
+        Some('h') => HOUR,
+        Some('d') => DAY,
+        Some('y') => YEAR,
+        Some(_) => return Err(Error::msg(format!("invalid duration '{}'", s))),
+    };
+
+    match num.trim().parse::<f64>() {
+        Ok(v) if v > 0.0 && v.is_finite() => Ok(v * unit),
+        _ => Err(Error::msg(format!("invalid duration '{}'", s))),
+    }
+}
+
+/// Formats seconds since Y1 D1 as `Y1 D1 0:00`.
+pub fn format_date(t: f64) -> String {
+    let t = t.max(0.0);
+    let year = (t / YEAR).floor();
+    let day = ((t - year * YEAR) / DAY).floor();
+    let secs = t - year * YEAR - day * DAY;
+    let hours = (secs / HOUR).floor();
+    let minutes = ((secs - hours * HOUR) / 60.0).floor();
+
+    format!(
+        "Y{} D{} {}:{:02}",
+        year as u64 + 1,
+        day as u64 + 1,
+        hours as u64,
+        minutes as u64
+    )
+}
+
+/// Formats a duration in seconds, minutes, hours, days, or years and days.
+pub fn format_duration(d: f64) -> String {
+    if d < 60.0 {
+        format!("{:.1} s", d)
+    } else if d < HOUR {
+        format!("{:.1} min", d / 60.0)
+    } else if d < DAY {
+        format!("{:.1} h", d / HOUR)
+    } else if d < YEAR {
+        format!("{:.1} d", d / DAY)
+    } else {
+        let years = (d / YEAR).floor();
+        format!("{} y {:.1} d", years as u64, (d - years * YEAR) / DAY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(parse_date("Y1 D1").unwrap(), 0.0);
+        assert_eq!(parse_date("y2d100").unwrap(), YEAR + 99.0 * DAY);
+        assert_eq!(parse_date(" 3600 ").unwrap(), HOUR);
+
+        for invalid in &["Y0 D1", "Y1 D0", "Y1 D427", "D1", "Y1", "NaN", "inf", "-1"] {
+            assert!(parse_date(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0.0), "Y1 D1 0:00");
+        assert_eq!(
+            format_date(YEAR + 99.0 * DAY + 5.0 * HOUR + 30.0 * 60.0),
+            "Y2 D100 5:30"
+        );
+
+        for date in &["Y1 D1", "Y2 D100", "Y10 D426"] {
+            let t = parse_date(date).unwrap();
+            assert_eq!(format_date(t), format!("{} 0:00", date));
+        }
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30").unwrap(), 30.0);
+        assert_eq!(parse_duration("30s").unwrap(), 30.0);
+        assert_eq!(parse_duration("2m").unwrap(), 120.0);
+        assert_eq!(parse_duration("6h").unwrap(), DAY);
+        assert_eq!(parse_duration("1.5d").unwrap(), 1.5 * DAY);
+        assert_eq!(parse_duration("2y").unwrap(), 2.0 * YEAR);
+
+        for invalid in &["", "h", "0d", "-1h", "1w", "NaN", "infd"] {
+            assert!(parse_duration(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(30.0), "30.0 s");
+        assert_eq!(format_duration(90.0), "1.5 min");
+        assert_eq!(
+            format_duration(parse_duration("6h").unwrap() / 2.0),
+            "3.0 h"
+        );
+        assert_eq!(format_duration(parse_duration("10d").unwrap()), "10.0 d");
+        assert_eq!(
+            format_duration(parse_duration("2y").unwrap() + parse_duration("3d").unwrap()),
+            "2 y 3.0 d"
+        );
+    }
+}
@@ -0,0 +1,592 @@
+//! Antenna catalog: the stock antennas merged with user-defined ones.
+//!
+//! User-defined antennas are read from a TOML or JSON file with a list of
+//! `antenna` entries:
+//!
+//! ```toml
+//! [[antenna]]
+//! name = "HG-2 Reflectron"
+//! aliases = ["HG-2"]
+//! power = 1.0e9
+//! combinable = true
+//! combinable_exponent = 0.75
+//! relay = true
+//! ground_station = false
+//! mass = 0.07
+//! cost = 600
+//! packet_size = 2.0
+//! packet_interval = 0.35
+//! packet_cost = 12.0
+//! ```
+//!
+//! `packet_size` (Mits), `packet_interval` (seconds) and `packet_cost` (EC per
+//! packet) are needed for `--science`, and should be given together.
+//!
+//! A ground station is scaled by the DSN modifier instead of the range modifier,
+//! and relays like a relay antenna. The stock DSN levels are ground stations.
+//!
+//! An entry with the name of an existing antenna overrides it, and keeps its
+//! `ground_station` unless the entry gives it.
+//!
+//! Entries are checked while the file is parsed, including names used twice, so
+//! that their errors have the line of the parser.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use ksp_commnet_calculator_core::antenna::{Antenna, Antennas};
+
+pub const DEFAULT_COMBINABLE_EXPONENT: f64 = 0.75;
+
+/// Packets of stock antennas as (name, size, interval, cost), from `packetSize`,
+/// `packetInterval` and `packetResourceCost` of `ModuleDataTransmitter` in the
+/// part configs of KSP 1.12 under `GameData/Squad/Parts`. The part of each is in
+/// the comment.
+const STOCK_PACKETS: &[(&str, f64, f64, f64)] = &[
+    // INTERNAL transmitter of the command pods and probe cores
+    ("Command Module", 2.0, 1.0, 12.0),
+    // longAntenna
+    ("Communotron 16", 2.0, 0.6, 12.0),
+    // SurfAntenna
+    ("Communotron 16-S", 2.0, 0.6, 12.0),
+    // mediumDishAntenna
+    ("Communotron DTS-M1", 2.0, 0.35, 12.0),
+    // HighGainAntenna
+    ("Communotron HG-55", 3.0, 0.15, 20.0),
+    // commDish
+    ("Communotron 88-88", 2.0, 0.1, 10.0),
+    // HighGainAntenna5.v2
+    ("HG-5", 2.0, 0.35, 18.0),
+    // RelayAntenna5
+    ("RA-2", 1.0, 0.35, 24.0),
+    // RelayAntenna50
+    ("RA-15", 2.0, 0.35, 24.0),
+    // RelayAntenna100
+    ("RA-100", 4.0, 0.35, 24.0),
+];
+
+/// Parts of stock antennas as (name, mass in tonnes, cost in funds).
+const STOCK_PARTS: &[(&str, f64, f64)] = &[
+    ("Communotron 16", 0.005, 300.0),
+    ("Communotron 16-S", 0.015, 300.0),
+    ("Communotron DTS-M1", 0.05, 900.0),
+    ("Communotron HG-55", 0.075, 1100.0),
+    ("Communotron 88-88", 0.1, 1500.0),
+    ("HG-5", 0.07, 600.0),
+    ("RA-2", 0.15, 1800.0),
+    ("RA-15", 0.3, 2400.0),
+    ("RA-100", 0.65, 3000.0),
+];
+
+/// Stock antennas of the tracking station.
+const STOCK_GROUND_STATIONS: &[&str] = &["DSN Lv.1", "DSN Lv.2", "DSN Lv.3"];
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub antenna: Antenna,
+    pub ground_station: bool,
+    pub mass: Option<f64>,
+    pub cost: Option<f64>,
+    pub packet: Option<Packet>,
+}
+
+/// Science transmission of an antenna.
+#[derive(Debug, Clone, Copy)]
+pub struct Packet {
+    /// Size of a packet in Mits.
+    pub size: f64,
+    /// Seconds between packets.
+    pub interval: f64,
+    /// Electric charge per packet.
+    pub cost: f64,
+}
+
+impl Packet {
+    /// Data rate in Mits per second.
+    pub fn rate(&self) -> f64 {
+        self.size / self.interval
+    }
+}
+
+pub struct Catalog {
+    entries: Vec<Entry>,
+}
+
+impl Catalog {
+    pub fn new() -> Catalog {
+        let entries = Antennas::new()
+            .iter()
+            .map(|a| {
+                let part = STOCK_PARTS.iter().find(|(n, _, _)| *n == a.name);
+                Entry {
+                    antenna: a.clone(),
+                    ground_station: STOCK_GROUND_STATIONS.contains(&a.name.as_str()),
+                    mass: part.map(|&(_, mass, _)| mass),
+                    cost: part.map(|&(_, _, cost)| cost),
+                    packet: STOCK_PACKETS.iter().find(|(n, _, _, _)| *n == a.name).map(
+                        |&(_, size, interval, cost)| Packet {
+                            size,
+                            interval,
+                            cost,
+                        },
+                    ),
+                }
+            })
+            .collect();
+
+        Catalog { entries }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Antenna> {
+        self.entry(name).map(|e| &e.antenna)
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| has_name(&e.antenna, name))
+    }
+
+    /// Whether `antenna` is a ground station, by its name.
+    pub fn is_ground_station(&self, antenna: &Antenna) -> bool {
+        self.entries
+            .iter()
+            .any(|e| e.antenna.name == antenna.name && e.ground_station)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Antenna> {
+        self.entries.iter().map(|e| &e.antenna)
+    }
+
+    pub fn merge_file(&mut self, path: &Path) -> Result<()> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?;
+
+        let defs = self
+            .parse_file(&source, is_json(path))
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?;
+        for def in defs {
+            self.insert(def);
+        }
+
+        Ok(())
+    }
+
+    /// Parses the entries of an antenna file, checked against the catalog.
+    fn parse_file(&self, source: &str, json: bool) -> Result<Vec<AntennaDef>> {
+        let seed = FileSeed { catalog: self };
+        if json {
+            let mut de = serde_json::Deserializer::from_str(source);
+            let defs = seed.deserialize(&mut de)?;
+            de.end()?;
+            Ok(defs)
+        } else {
+            let mut de = toml::Deserializer::new(source);
+            let defs = seed.deserialize(&mut de)?;
+            de.end()?;
+            Ok(defs)
+        }
+    }
+
+    /// Adds or overrides an antenna, unless its name or aliases are used by another antenna.
+    pub fn add(&mut self, def: AntennaDef) -> Result<()> {
+        def.validate()?;
+        if let Some((n, other)) = self.conflict(&def) {
+            return Err(Error::msg(format!(
+                "'{}' of '{}' is already used by '{}'",
+                n, def.name, other
+            )));
+        }
+
+        self.insert(def);
+        Ok(())
+    }
+
+    /// Finds a name or alias of `def` used by another antenna.
+    fn conflict(&self, def: &AntennaDef) -> Option<(String, String)> {
+        for n in std::iter::once(&def.name).chain(def.aliases.iter()) {
+            let other = self
+                .entries
+                .iter()
+                .find(|e| e.antenna.name != def.name && has_name(&e.antenna, n));
+            if let Some(e) = other {
+                return Some((n.clone(), e.antenna.name.clone()));
+            }
+        }
+        None
+    }
+
+    fn insert(&mut self, def: AntennaDef) {
+        let i = self.entries.iter().position(|e| e.antenna.name == def.name);
+        let ground_station = def
+            .ground_station
+            .or_else(|| i.map(|i| self.entries[i].ground_station))
+            .unwrap_or(false);
+        let entry = def.into_entry(ground_station);
+        match i {
+            Some(i) => self.entries[i] = entry,
+            None => self.entries.push(entry),
+        }
+    }
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Catalog::new()
+    }
+}
+
+/// Antenna file, a table with a list of `antenna` entries.
+struct FileSeed<'a> {
+    catalog: &'a Catalog,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for FileSeed<'a> {
+    type Value = Vec<AntennaDef>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for FileSeed<'a> {
+    type Value = Vec<AntennaDef>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a table of antenna entries")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut defs = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if key != "antenna" {
+                return Err(de::Error::unknown_field(&key, &["antenna"]));
+            }
+            if defs.is_some() {
+                return Err(de::Error::duplicate_field("antenna"));
+            }
+            defs = Some(map.next_value_seed(EntriesSeed {
+                catalog: self.catalog,
+            })?);
+        }
+        Ok(defs.unwrap_or_default())
+    }
+}
+
+/// Entries of an antenna file, whose names and aliases are checked against the
+/// catalog and the previous entries while they are deserialized.
+struct EntriesSeed<'a> {
+    catalog: &'a Catalog,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for EntriesSeed<'a> {
+    type Value = Vec<AntennaDef>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for EntriesSeed<'a> {
+    type Value = Vec<AntennaDef>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a list of antenna entries")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        // Names and aliases defined in the file, to detect duplicates within it.
+        let mut defined: HashMap<String, String> = HashMap::new();
+        let mut defs = Vec::new();
+        while let Some(CheckedDef(def)) = seq.next_element()? {
+            for n in std::iter::once(&def.name).chain(def.aliases.iter()) {
+                if let Some(other) = defined.insert(n.clone(), def.name.clone()) {
+                    return Err(de::Error::custom(format!(
+                        "'{}' of '{}' is already used by '{}'",
+                        n, def.name, other
+                    )));
+                }
+            }
+
+            if let Some((n, other)) = self.catalog.conflict(&def) {
+                return Err(de::Error::custom(format!(
+                    "'{}' of '{}' is already used by '{}'",
+                    n, def.name, other
+                )));
+            }
+
+            defs.push(def);
+        }
+        Ok(defs)
+    }
+}
+
+/// Definition validated while it is deserialized, so that the parser reports its position.
+#[derive(Debug)]
+struct CheckedDef(AntennaDef);
+
+impl<'de> Deserialize<'de> for CheckedDef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let def = AntennaDef::deserialize(deserializer)?;
+        def.validate().map_err(de::Error::custom)?;
+        Ok(CheckedDef(def))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AntennaDef {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub power: f64,
+    #[serde(default = "default_combinable")]
+    pub combinable: bool,
+    #[serde(default = "default_combinable_exponent")]
+    pub combinable_exponent: f64,
+    #[serde(default)]
+    pub relay: bool,
+    /// Unset on an override to keep the flag of the overridden antenna.
+    #[serde(default)]
+    pub ground_station: Option<bool>,
+    pub mass: Option<f64>,
+    pub cost: Option<f64>,
+    pub packet_size: Option<f64>,
+    pub packet_interval: Option<f64>,
+    pub packet_cost: Option<f64>,
+}
+
+impl AntennaDef {
+    /// Checks that the values are in range, so that they never produce NaN later.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(Error::msg("antenna name is empty"));
+        }
+        if self.power <= 0.0 || !self.power.is_finite() {
+            return Err(Error::msg(format!(
+                "power of '{}' should be positive",
+                self.name
+            )));
+        }
+        if self.combinable_exponent < 0.0 || !self.combinable_exponent.is_finite() {
+            return Err(Error::msg(format!(
+                "combinable_exponent of '{}' should not be negative",
+                self.name
+            )));
+        }
+        for (field, value) in &[("mass", self.mass), ("cost", self.cost)] {
+            if let Some(v) = value {
+                if *v < 0.0 || !v.is_finite() {
+                    return Err(Error::msg(format!(
+                        "{} of '{}' should not be negative",
+                        field, self.name
+                    )));
+                }
+            }
+        }
+
+        self.packet()?;
+        Ok(())
+    }
+
+    /// Packet of the antenna, if all of its values are given.
+    pub fn packet(&self) -> Result<Option<Packet>> {
+        match (self.packet_size, self.packet_interval, self.packet_cost) {
+            (None, None, None) => Ok(None),
+            (Some(size), Some(interval), Some(cost))
+                if size > 0.0 && interval > 0.0 && cost >= 0.0 =>
+            {
+                Ok(Some(Packet {
+                    size,
+                    interval,
+                    cost,
+                }))
+            }
+            (Some(_), Some(_), Some(_)) => Err(Error::msg(format!(
+                "packet values of '{}' should be positive",
+                self.name
+            ))),
+            _ => Err(Error::msg(format!(
+                "packet_size, packet_interval and packet_cost of '{}' should be given together",
+                self.name
+            ))),
+        }
+    }
+
+    fn into_entry(self, ground_station: bool) -> Entry {
+        // Checked when the definition is read.
+        let packet = self.packet().unwrap_or_default();
+        Entry {
+            antenna: Antenna {
+                name: self.name,
+                aliases: self.aliases,
+                power: self.power,
+                is_relay: self.relay,
+                combinable_exponent: if self.combinable {
+                    Some(self.combinable_exponent)
+                } else {
+                    None
+                },
+            },
+            ground_station,
+            mass: self.mass,
+            cost: self.cost,
+            packet,
+        }
+    }
+}
+
+fn default_combinable() -> bool {
+    true
+}
+
+fn default_combinable_exponent() -> f64 {
+    DEFAULT_COMBINABLE_EXPONENT
+}
+
+fn has_name(antenna: &Antenna, name: &str) -> bool {
+    antenna.name == name || antenna.aliases.iter().any(|a| a == name)
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.eq_ignore_ascii_case("json"))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stock_parts() {
+        let catalog = Catalog::new();
+
+        let hg5 = catalog.entry("HG-5").unwrap();
+        assert_eq!(hg5.mass, Some(0.07));
+        assert_eq!(hg5.cost, Some(600.0));
+        assert!(!hg5.ground_station);
+
+        let dsn = catalog.entry("DSN Lv.2").unwrap();
+        assert_eq!(dsn.mass, None);
+        assert!(dsn.ground_station);
+    }
+
+    #[test]
+    fn invalid_entry_at_parser_line() {
+        let source = r#"{
+  "antenna": [
+    { "name": "A", "power": 1.0e6 },
+    {
+      "name": "B",
+      "power": -1.0
+    }
+  ]
+}"#;
+        let e = Catalog::new().parse_file(source, true).unwrap_err();
+        assert!(e.to_string().contains("power of 'B' should be positive"));
+        assert!(e.to_string().contains("line 7"));
+
+        let source = r#"
+[[antenna]]
+name = "A"
+power = 1.0e6
+mass = -0.1
+"#;
+        let e = Catalog::new().parse_file(source, false).unwrap_err();
+        assert!(e.to_string().contains("mass of 'A' should not be negative"));
+        assert!(e.to_string().contains("line "));
+    }
+
+    #[test]
+    fn duplicate_name_at_parser_line() {
+        let source = r#"{
+  "antenna": [
+    { "name": "A", "power": 1.0e6 },
+    { "name": "B", "aliases": ["A"], "power": 1.0e6 }
+  ]
+}"#;
+        let e = Catalog::new().parse_file(source, true).unwrap_err();
+        assert!(e.to_string().contains("'A' of 'B' is already used by 'A'"));
+        assert!(e.to_string().contains("line 4"));
+
+        let source = r#"
+[[antenna]]
+name = "A"
+aliases = ["HG-5"]
+power = 1.0e6
+"#;
+        let e = Catalog::new().parse_file(source, false).unwrap_err();
+        assert!(e
+            .to_string()
+            .contains("'HG-5' of 'A' is already used by 'HG-5'"));
+        assert!(e.to_string().contains("line "));
+    }
+
+    #[test]
+    fn override_keeps_ground_station() {
+        let source = r#"
+[[antenna]]
+name = "DSN Lv.2"
+power = 1.0e11
+
+[[antenna]]
+name = "DSN Lv.3"
+power = 1.0e12
+ground_station = false
+"#;
+        let mut catalog = Catalog::new();
+        for def in catalog.parse_file(source, false).unwrap() {
+            catalog.insert(def);
+        }
+
+        let dsn2 = catalog.entry("DSN Lv.2").unwrap();
+        assert_eq!(dsn2.antenna.power, 1.0e11);
+        assert!(dsn2.ground_station);
+        assert!(!catalog.entry("DSN Lv.3").unwrap().ground_station);
+    }
+
+    #[test]
+    fn override_drops_aliases() {
+        let mut catalog = Catalog::new();
+        catalog
+            .add(AntennaDef {
+                name: "HG-2".to_owned(),
+                aliases: vec!["HG-2 Reflectron".to_owned()],
+                power: 1.0e9,
+                combinable: true,
+                combinable_exponent: DEFAULT_COMBINABLE_EXPONENT,
+                relay: true,
+                ground_station: None,
+                mass: None,
+                cost: None,
+                packet_size: None,
+                packet_interval: None,
+                packet_cost: None,
+            })
+            .unwrap();
+        assert!(catalog.get("HG-2 Reflectron").is_some());
+
+        catalog
+            .add(AntennaDef {
+                name: "HG-2".to_owned(),
+                aliases: Vec::new(),
+                power: 2.0e9,
+                combinable: true,
+                combinable_exponent: DEFAULT_COMBINABLE_EXPONENT,
+                relay: true,
+                ground_station: None,
+                mass: None,
+                cost: None,
+                packet_size: None,
+                packet_interval: None,
+                packet_cost: None,
+            })
+            .unwrap();
+        assert!(catalog.get("HG-2 Reflectron").is_none());
+        assert_eq!(catalog.get("HG-2").unwrap().power, 2.0e9);
+    }
+}
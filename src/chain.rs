@@ -0,0 +1,135 @@
+use anyhow::{Error, Result};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::catalog::Catalog;
+use crate::cli::{parse_modifiers, print_endpoint};
+use crate::endpoint::{EndpointBuilder, Role};
+use crate::metric::parse_distance;
+use crate::render::format_strength;
+use crate::signal::strength_at;
+use crate::DEFAULT_TO;
+
+pub const NAME: &str = "chain";
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME)
+        .about("Evaluate a multi-hop relay chain")
+        .arg(
+            Arg::with_name("node")
+                .short("n")
+                .long("node")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true)
+                .required(true)
+                .value_name("ANTENNAS")
+                .help("Endpoint in chain order, as comma-separated antenna specifiers"),
+        )
+        .arg(
+            Arg::with_name("distance")
+                .short("d")
+                .long("distance")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true)
+                .required(true)
+                .value_name("DISTANCE")
+                .help("Distance of each hop, in chain order"),
+        )
+}
+
+pub fn chain(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let modifiers = parse_modifiers(matches)?;
+    let specs: Vec<&str> = matches.values_of("node").unwrap_or_default().collect();
+
+    // Vessels between both ends relay the signal.
+    let mut nodes = Vec::new();
+    for (i, spec) in specs.iter().enumerate() {
+        let role = if i == 0 || i == specs.len() - 1 {
+            None
+        } else {
+            Some(Role::Relay)
+        };
+        let endpoint = EndpointBuilder::new(antennas)
+            .role(role)
+            .modifiers(modifiers)
+            .build(spec.split(',').map(str::trim), DEFAULT_TO)?;
+        nodes.push(endpoint);
+    }
+
+    let mut distances = Vec::new();
+    for d in matches.values_of("distance").unwrap_or_default() {
+        distances.push(parse_distance(d)?);
+    }
+
+    if nodes.len() < 2 {
+        return Err(Error::msg("chain needs at least 2 nodes"));
+    }
+    if distances.len() != nodes.len() - 1 {
+        return Err(Error::msg(format!(
+            "{} nodes need {} distances, but {} given",
+            nodes.len(),
+            nodes.len() - 1,
+            distances.len()
+        )));
+    }
+
+    let hops = evaluate(&nodes, &distances);
+
+    println!();
+    for (i, node) in nodes.iter().enumerate() {
+        println!(" Node {}:", i + 1);
+        print_endpoint(antennas, node, &modifiers);
+    }
+    println!();
+
+    println!(" |  Hop   | Distance | Max distance | Strength |");
+    println!(" |:-------|---------:|-------------:|---------:|");
+    for (i, hop) in hops.iter().enumerate() {
+        println!(
+            " | {:<6} | {:>8} | {:>12} | {:>8} |",
+            format!("{} -> {}", i + 1, i + 2),
+            format!("{}m", MetricPrefix(hop.distance)),
+            format!("{}m", MetricPrefix(hop.max_distance)),
+            format_strength(hop.strength),
+        );
+    }
+    println!();
+
+    println!(
+        " Total strength: {}",
+        format_strength(total_strength(&hops))
+    );
+    println!();
+
+    Ok(())
+}
+
+struct Hop {
+    distance: f64,
+    max_distance: f64,
+    strength: Option<f64>,
+}
+
+fn evaluate(nodes: &[Endpoint], distances: &[f64]) -> Vec<Hop> {
+    nodes
+        .windows(2)
+        .zip(distances.iter())
+        .map(|(pair, &distance)| {
+            let max_distance = pair[0].range_to(&pair[1]).max_distance();
+            Hop {
+                distance,
+                max_distance,
+                strength: strength_at(max_distance, distance),
+            }
+        })
+        .collect()
+}
+
+/// End-to-end strength, which is the product of the strengths of all hops.
+fn total_strength(hops: &[Hop]) -> Option<f64> {
+    hops.iter().map(|h| h.strength).product()
+}
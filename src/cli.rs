@@ -0,0 +1,693 @@
+//! Command line interface of the `ksp-commnet-calculator-cli` command.
+//!
+//! The binary only calls [`run`]. Subcommands are in their own modules, and
+//! run with the matches of their subcommand and the antenna catalog.
+
+use std::path::Path;
+
+use anyhow::{Error, Result};
+use clap::{crate_name, App, Arg, ArgMatches};
+
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::bodies::System;
+use crate::catalog::Catalog;
+use crate::craft::Craft;
+use crate::endpoint::{Modifiers, Role};
+use crate::geometry::{separation, OrbitSpec, Section, Sections};
+use crate::metric::parse_distance;
+use crate::render::delimited::{self, Delimiter};
+use crate::render::{format_strength, json, markdown};
+use crate::report::{parse_science, Design, EndpointSummary, Report, ReportBuilder};
+use crate::save::Save;
+use crate::scenario::Scenario;
+use crate::{
+    audit, chain, constellation, coverage, gamedata, repl, serve, solve, timeline, workbench,
+    DEFAULT_FROM,
+};
+
+/// Parses the arguments of the process and runs the command.
+pub fn run() -> Result<()> {
+    run_matches(app().get_matches())
+}
+
+/// Arguments of the command and its subcommands.
+pub fn app() -> App<'static, 'static> {
+    App::new(crate_name!())
+        .arg(
+            Arg::with_name("from")
+                .short("f")
+                .long("from")
+                .multiple(true)
+                .takes_value(true)
+                .default_value(DEFAULT_FROM),
+        )
+        .arg(
+            Arg::with_name("to")
+                .short("t")
+                .long("to")
+                .multiple(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("design")
+                .long("design")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true)
+                .use_delimiter(false)
+                .value_name("NAME=ANTENNAS")
+                .conflicts_with_all(&["to", "to-vessel", "to-craft"])
+                .help("Compare a design of 'to' with others, like probeA=2:HG-5,RA-2"),
+        )
+        .arg(
+            Arg::with_name("from-role")
+                .long("from-role")
+                .takes_value(true)
+                .possible_values(&["relay", "direct"])
+                .help("Count only relay antennas of 'from' if relay"),
+        )
+        .arg(
+            Arg::with_name("to-role")
+                .long("to-role")
+                .takes_value(true)
+                .possible_values(&["relay", "direct"])
+                .help("Count only relay antennas of 'to' if relay"),
+        )
+        .arg(
+            Arg::with_name("antennas")
+                .short("A")
+                .long("antennas")
+                .help("Print antennas"),
+        )
+        .arg(
+            Arg::with_name("lenient")
+                .long("lenient")
+                .help("Skip unknown antennas with a warning instead of failing"),
+        )
+        .arg(
+            Arg::with_name("distance")
+                .short("d")
+                .long("distance")
+                .takes_value(true)
+                .value_name("DISTANCE")
+                .help("Also print the strength at DISTANCE (e.g. 12.5Gm, 84Mm, 3.4e9)"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["markdown", "json", "csv", "tsv"])
+                .default_value("markdown")
+                .help("Output format"),
+        )
+        .arg(
+            Arg::with_name("no-header")
+                .long("no-header")
+                .help("Omit the header row of csv/tsv output"),
+        )
+        .arg(
+            Arg::with_name("science")
+                .long("science")
+                .takes_value(true)
+                .value_name("MITS")
+                .help("Also print the time and EC to transmit MITS of science from 'to'"),
+        )
+        .arg(
+            Arg::with_name("scenario")
+                .long("scenario")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Check the cases of a TOML, YAML or JSON file, and fail if any fails"),
+        )
+        .arg(
+            Arg::with_name("save")
+                .long("save")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("KSP save file (persistent.sfs) for --from-vessel and --to-vessel"),
+        )
+        .arg(
+            Arg::with_name("from-vessel")
+                .long("from-vessel")
+                .takes_value(true)
+                .value_name("NAME")
+                .requires("save")
+                .help("Use antennas of a vessel in the save as 'from'"),
+        )
+        .arg(
+            Arg::with_name("to-vessel")
+                .long("to-vessel")
+                .takes_value(true)
+                .value_name("NAME")
+                .requires("save")
+                .help("Use antennas of a vessel in the save as 'to'"),
+        )
+        .arg(
+            Arg::with_name("from-craft")
+                .long("from-craft")
+                .takes_value(true)
+                .value_name("PATH")
+                .conflicts_with("from-vessel")
+                .help("Use antennas of a .craft file as 'from'"),
+        )
+        .arg(
+            Arg::with_name("to-craft")
+                .long("to-craft")
+                .takes_value(true)
+                .value_name("PATH")
+                .conflicts_with("to-vessel")
+                .help("Use antennas of a .craft file as 'to'"),
+        )
+        .arg(
+            Arg::with_name("from-body")
+                .long("from-body")
+                .takes_value(true)
+                .value_name("BODY")
+                .requires("to-body")
+                .help("Body which 'from' orbits, for the distance between two orbits"),
+        )
+        .arg(
+            Arg::with_name("from-altitude")
+                .long("from-altitude")
+                .takes_value(true)
+                .value_name("DISTANCE")
+                .requires("from-body")
+                .help("Altitude of the circular orbit of 'from' (default: on the surface)"),
+        )
+        .arg(
+            Arg::with_name("from-sma")
+                .long("from-sma")
+                .takes_value(true)
+                .value_name("DISTANCE")
+                .requires("from-body")
+                .conflicts_with("from-altitude")
+                .help("Semi-major axis of the orbit of 'from'"),
+        )
+        .arg(
+            Arg::with_name("from-ecc")
+                .long("from-ecc")
+                .takes_value(true)
+                .value_name("ECCENTRICITY")
+                .requires("from-sma")
+                .help("Eccentricity of the orbit of 'from'"),
+        )
+        .arg(
+            Arg::with_name("to-body")
+                .long("to-body")
+                .takes_value(true)
+                .value_name("BODY")
+                .requires("from-body")
+                .help("Body which 'to' orbits, for the distance between two orbits"),
+        )
+        .arg(
+            Arg::with_name("to-altitude")
+                .long("to-altitude")
+                .takes_value(true)
+                .value_name("DISTANCE")
+                .requires("to-body")
+                .help("Altitude of the circular orbit of 'to' (default: on the surface)"),
+        )
+        .arg(
+            Arg::with_name("to-sma")
+                .long("to-sma")
+                .takes_value(true)
+                .value_name("DISTANCE")
+                .requires("to-body")
+                .conflicts_with("to-altitude")
+                .help("Semi-major axis of the orbit of 'to'"),
+        )
+        .arg(
+            Arg::with_name("to-ecc")
+                .long("to-ecc")
+                .takes_value(true)
+                .value_name("ECCENTRICITY")
+                .requires("to-sma")
+                .help("Eccentricity of the orbit of 'to'"),
+        )
+        .arg(
+            Arg::with_name("range-modifier")
+                .long("range-modifier")
+                .takes_value(true)
+                .global(true)
+                .default_value("1")
+                .help("Range Modifier of the CommNet difficulty settings"),
+        )
+        .arg(
+            Arg::with_name("dsn-modifier")
+                .long("dsn-modifier")
+                .takes_value(true)
+                .global(true)
+                .default_value("1")
+                .help("DSN Modifier of the CommNet difficulty settings"),
+        )
+        .arg(
+            Arg::with_name("antenna-file")
+                .long("antenna-file")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true)
+                .global(true)
+                .value_name("PATH")
+                .help("Add or override antennas from a TOML or JSON file"),
+        )
+        .arg(
+            Arg::with_name("gamedata")
+                .long("gamedata")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true)
+                .global(true)
+                .value_name("DIR")
+                .help("Import antennas from part configs in a GameData directory"),
+        )
+        .arg(
+            Arg::with_name("bodies")
+                .long("bodies")
+                .takes_value(true)
+                .global(true)
+                .value_name("PATH")
+                .help("Use the planetary system of a TOML or JSON file instead of the stock one"),
+        )
+        .arg(
+            Arg::with_name("rescale")
+                .long("rescale")
+                .takes_value(true)
+                .global(true)
+                .value_name("FACTOR")
+                .help("Scale orbits and sizes of the bodies (e.g. 2.5)"),
+        )
+        .subcommand(solve::subcommand())
+        .subcommand(chain::subcommand())
+        .subcommand(audit::subcommand())
+        .subcommand(timeline::subcommand())
+        .subcommand(coverage::subcommand())
+        .subcommand(constellation::subcommand())
+        .subcommand(repl::subcommand())
+        .subcommand(workbench::subcommand())
+        .subcommand(serve::subcommand())
+}
+
+/// Runs the command with the matches of [`app`].
+pub fn run_matches(matches: ArgMatches) -> Result<()> {
+    let antennas = load_catalog(matches.subcommand().1.unwrap_or(&matches))?;
+
+    if let Some(m) = matches.subcommand_matches(solve::NAME) {
+        return solve::solve(m, &antennas);
+    }
+    if let Some(m) = matches.subcommand_matches(chain::NAME) {
+        return chain::chain(m, &antennas);
+    }
+    if let Some(m) = matches.subcommand_matches(audit::NAME) {
+        return audit::audit(m, &antennas);
+    }
+    if let Some(m) = matches.subcommand_matches(timeline::NAME) {
+        return timeline::timeline(m, &antennas);
+    }
+    if let Some(m) = matches.subcommand_matches(coverage::NAME) {
+        return coverage::coverage(m, &antennas);
+    }
+    if let Some(m) = matches.subcommand_matches(constellation::NAME) {
+        return constellation::constellation(m, &antennas);
+    }
+    if let Some(m) = matches.subcommand_matches(repl::NAME) {
+        return repl::interactive(m, &antennas);
+    }
+    if let Some(m) = matches.subcommand_matches(workbench::NAME) {
+        return workbench::workbench(m, &antennas);
+    }
+    if let Some(m) = matches.subcommand_matches(serve::NAME) {
+        return serve::serve(m, &antennas);
+    }
+
+    if matches.is_present("antennas") {
+        print_antennas(&antennas);
+        return Ok(());
+    }
+    if let Some(path) = matches.value_of("scenario") {
+        return run_scenario(&matches, &antennas, Path::new(path));
+    }
+
+    print_dists(matches, antennas)
+}
+
+fn load_catalog(matches: &ArgMatches) -> Result<Catalog> {
+    let mut catalog = Catalog::new();
+
+    for dir in matches.values_of("gamedata").unwrap_or_default() {
+        let import = gamedata::import_gamedata(Path::new(dir))?;
+        for w in import.warnings {
+            eprintln!("Warning: {}", w);
+        }
+        for def in import.antennas {
+            let name = def.name.clone();
+            if let Err(e) = catalog.add(def) {
+                eprintln!("Warning: skipped '{}': {}", name, e);
+            }
+        }
+    }
+
+    for path in matches.values_of("antenna-file").unwrap_or_default() {
+        catalog.merge_file(Path::new(path))?;
+    }
+    Ok(catalog)
+}
+
+/// Planetary system of `--bodies`, or the stock one, rescaled by `--rescale`.
+pub(crate) fn load_system(matches: &ArgMatches) -> Result<System> {
+    let mut system = match matches.value_of("bodies") {
+        Some(path) => System::load(Path::new(path))?,
+        None => System::stock(),
+    };
+    if let Some(s) = matches.value_of("rescale") {
+        system.rescale(parse_factor("rescale", s)?);
+    }
+    Ok(system)
+}
+
+/// Stock sections, or ones generated from the system if `--bodies` or `--rescale` is given.
+pub(crate) fn load_sections(matches: &ArgMatches) -> Result<Sections> {
+    if matches.is_present("bodies") || matches.is_present("rescale") {
+        Sections::generate(&load_system(matches)?)
+    } else {
+        Ok(Sections::stock())
+    }
+}
+
+pub(crate) fn print_antennas(antennas: &Catalog) {
+    println!("Available antennas:");
+    for a in antennas.iter() {
+        print!("    {}", a.name);
+        if !a.aliases.is_empty() {
+            print!(" (");
+            for (i, al) in a.aliases.iter().enumerate() {
+                if i > 0 {
+                    print!(", ");
+                }
+                print!("{}", al);
+            }
+            print!(")");
+        }
+        println!();
+    }
+}
+
+fn print_dists(matches: ArgMatches, antennas: Catalog) -> Result<()> {
+    let save = match matches.value_of("save") {
+        Some(path) => Some(Save::load(Path::new(path))?),
+        None => None,
+    };
+    let from_specs = endpoint_specs(&matches, "from", save.as_ref(), &antennas)?;
+
+    let distance = match matches.value_of("distance") {
+        Some(d) => Some(parse_distance(d)?),
+        None => None,
+    };
+
+    let builder = report_builder(&matches, &antennas, distance)?
+        .lenient(matches.is_present("lenient"))
+        .from_role(parse_role(matches.value_of("from-role"))?)
+        .to_role(parse_role(matches.value_of("to-role"))?);
+    let from = builder.build_from(from_specs.iter().map(String::as_str))?;
+
+    if let Some(designs) = matches.values_of("design") {
+        let reports = builder.build_designs(&from, designs)?;
+        return print_comparison(&matches, &reports);
+    }
+
+    let to_specs = endpoint_specs(&matches, "to", save.as_ref(), &antennas)?;
+    let to = builder.build_to(to_specs.iter().map(String::as_str))?;
+
+    write_report(&matches, &builder.build(&from, &to)?)
+}
+
+fn print_comparison(matches: &ArgMatches, designs: &[Design]) -> Result<()> {
+    let no_header = matches.is_present("no-header");
+    match matches.value_of("format") {
+        Some("json") => println!("{}", json::render_comparison(designs)?),
+        Some("csv") => print!(
+            "{}",
+            delimited::render_comparison(designs, Delimiter::Comma, !no_header)
+        ),
+        Some("tsv") => print!(
+            "{}",
+            delimited::render_comparison(designs, Delimiter::Tab, !no_header)
+        ),
+        _ => print!("{}", markdown::render_comparison(designs)),
+    }
+    Ok(())
+}
+
+/// Prints the report between `from` and `to` in the format and with the options of `matches`.
+pub(crate) fn print_report(
+    matches: &ArgMatches,
+    antennas: &Catalog,
+    from: &Endpoint,
+    to: &Endpoint,
+    distance: Option<f64>,
+) -> Result<()> {
+    let report = report_builder(matches, antennas, distance)?.build(from, to)?;
+    write_report(matches, &report)
+}
+
+/// Prints the report in the format of `matches`.
+fn write_report(matches: &ArgMatches, report: &Report) -> Result<()> {
+    let no_header = matches.is_present("no-header");
+    match matches.value_of("format") {
+        Some("json") => println!("{}", json::render(report)?),
+        Some("csv") => print!(
+            "{}",
+            delimited::render(report, Delimiter::Comma, !no_header)
+        ),
+        Some("tsv") => print!("{}", delimited::render(report, Delimiter::Tab, !no_header)),
+        _ => print!("{}", markdown::render(report)),
+    }
+    Ok(())
+}
+
+/// Prints the result of each case of the scenario, and fails if any case fails.
+fn run_scenario(matches: &ArgMatches, antennas: &Catalog, path: &Path) -> Result<()> {
+    let scenario = Scenario::load(path)?;
+    let report = ReportBuilder::new(antennas)
+        .modifiers(parse_modifiers(matches)?)
+        .sections(load_sections(matches)?);
+
+    println!();
+    println!(
+        " | {:<25} | {:<25} | {:>8} | {:>8} | {:<6} |",
+        "Case", "Weakest", "Strength", "Required", "Result"
+    );
+    println!(
+        " |:--------------------------|:--------------------------|---------:|---------:|:-------|"
+    );
+
+    let mut failed = 0;
+    for case in &scenario.cases {
+        let required = format_strength(Some(case.min_strength / 100.0));
+        match case.evaluate(&report) {
+            Ok(outcome) => {
+                let passed = outcome.passed();
+                if !passed {
+                    failed += 1;
+                }
+                let (label, strength) = match outcome.weakest() {
+                    Some(c) => (c.label.as_str(), format_strength(c.strength)),
+                    None => ("", String::new()),
+                };
+                println!(
+                    " | {:<25} | {:<25} | {:>8} | {:>8} | {:<6} |",
+                    case.name,
+                    label,
+                    strength,
+                    required,
+                    if passed { "PASS" } else { "FAIL" }
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("Error: case '{}': {}", case.name, e);
+                println!(
+                    " | {:<25} | {:<25} | {:>8} | {:>8} | {:<6} |",
+                    case.name, "", "", required, "ERROR"
+                );
+            }
+        }
+    }
+
+    let total = scenario.cases.len();
+    println!();
+    println!(" {} of {} cases passed", total - failed, total);
+    println!();
+
+    if failed > 0 {
+        return Err(Error::msg(format!("{} of {} cases failed", failed, total)));
+    }
+    Ok(())
+}
+
+/// Report builder with the sections, orbits and science of `matches`.
+fn report_builder<'a>(
+    matches: &ArgMatches,
+    antennas: &'a Catalog,
+    distance: Option<f64>,
+) -> Result<ReportBuilder<'a>> {
+    let science = match matches.value_of("science") {
+        Some(s) => Some(parse_science(s)?),
+        None => None,
+    };
+
+    let mut builder = ReportBuilder::new(antennas)
+        .modifiers(parse_modifiers(matches)?)
+        .sections(load_sections(matches)?)
+        .distance(distance)
+        .science(science);
+    if let Some(section) = orbit_section(matches)? {
+        builder = builder.section(section);
+    }
+    Ok(builder)
+}
+
+/// Antenna specifiers of the `side` endpoint, from the vessel in the save or the craft if given.
+fn endpoint_specs(
+    matches: &ArgMatches,
+    side: &str,
+    save: Option<&Save>,
+    antennas: &Catalog,
+) -> Result<Vec<String>> {
+    let vessel_arg = format!("{}-vessel", side);
+    let craft_arg = format!("{}-craft", side);
+
+    let (name, part_antennas, source_arg) =
+        if let (Some(v), Some(s)) = (matches.value_of(&vessel_arg), save) {
+            let vessel = s.vessel(v)?;
+            (vessel.name.clone(), vessel.antennas(antennas), vessel_arg)
+        } else if let Some(path) = matches.value_of(&craft_arg) {
+            let craft = Craft::load(Path::new(path))?;
+            let part_antennas = craft.antennas(antennas);
+            for (part, antenna) in &part_antennas.recognized {
+                eprintln!("Craft '{}': {} -> {}", craft.name, part, antenna);
+            }
+            (craft.name, part_antennas, craft_arg)
+        } else {
+            return Ok(matches
+                .values_of(side)
+                .unwrap_or_default()
+                .map(str::to_owned)
+                .collect());
+        };
+
+    if matches.occurrences_of(side) > 0 {
+        return Err(Error::msg(format!(
+            "--{} and --{} cannot be used together",
+            side, source_arg
+        )));
+    }
+
+    for (part, module) in &part_antennas.unknown {
+        eprintln!(
+            "Warning: unknown antenna part on '{}': {} ({})",
+            name, part, module
+        );
+    }
+    if part_antennas.counts.is_empty() {
+        return Err(Error::msg(format!("'{}' has no antenna", name)));
+    }
+
+    Ok(part_antennas.specs())
+}
+
+/// Section between the orbits of `--from-body` and `--to-body`, if given.
+fn orbit_section(matches: &ArgMatches) -> Result<Option<Section>> {
+    let system = load_system(matches)?;
+    let (from, to) = match (
+        orbit_spec(matches, "from", &system)?,
+        orbit_spec(matches, "to", &system)?,
+    ) {
+        (Some(f), Some(t)) => (f, t),
+        _ => return Ok(None),
+    };
+
+    let (min, max) = separation(&system, &from, &to)?;
+    let name = format!(
+        "{} - {}",
+        orbit_label(&from, &system),
+        orbit_label(&to, &system)
+    );
+    Ok(Some(Section { name, min, max }))
+}
+
+fn orbit_spec(matches: &ArgMatches, side: &str, system: &System) -> Result<Option<OrbitSpec>> {
+    let name = match matches.value_of(format!("{}-body", side)) {
+        Some(n) => n,
+        None => return Ok(None),
+    };
+    let body = system
+        .get(name)
+        .ok_or_else(|| Error::msg(format!("unknown body '{}'", name)))?;
+
+    let spec = if let Some(sma) = matches.value_of(format!("{}-sma", side)) {
+        let ecc = match matches.value_of(format!("{}-ecc", side)) {
+            Some(e) => e
+                .parse()
+                .map_err(|_| Error::msg(format!("invalid eccentricity '{}'", e)))?,
+            None => 0.0,
+        };
+        OrbitSpec::elliptic(body, parse_distance(sma)?, ecc)?
+    } else {
+        let altitude = match matches.value_of(format!("{}-altitude", side)) {
+            Some(a) => parse_distance(a)?,
+            None => 0.0,
+        };
+        OrbitSpec::circular(body, altitude)
+    };
+    spec.validate(body)?;
+
+    Ok(Some(spec))
+}
+
+/// Label of an orbit in the section column, like `Kerbin 2.868Mm` (altitude), or the body name on the surface.
+fn orbit_label(orbit: &OrbitSpec, system: &System) -> String {
+    let radius = system.get(&orbit.body).map(|b| b.radius).unwrap_or(0.0);
+    let (pe, ap) = (orbit.periapsis - radius, orbit.apoapsis - radius);
+
+    if ap <= 0.0 {
+        orbit.body.clone()
+    } else if (ap - pe).abs() < 1.0 {
+        format!("{} {}m", orbit.body, MetricPrefix(pe))
+    } else {
+        format!("{} {}m-{}m", orbit.body, MetricPrefix(pe), MetricPrefix(ap))
+    }
+}
+
+pub(crate) fn parse_role(s: Option<&str>) -> Result<Option<Role>> {
+    s.map(str::parse).transpose()
+}
+
+pub(crate) fn parse_modifiers(matches: &ArgMatches) -> Result<Modifiers> {
+    Ok(Modifiers {
+        range: parse_modifier(matches, "range-modifier")?,
+        dsn: parse_modifier(matches, "dsn-modifier")?,
+    })
+}
+
+fn parse_modifier(matches: &ArgMatches, name: &str) -> Result<f64> {
+    parse_factor(name, matches.value_of(name).unwrap_or("1"))
+}
+
+pub(crate) fn parse_factor(name: &str, s: &str) -> Result<f64> {
+    match s.parse::<f64>() {
+        Ok(v) if v > 0.0 && v.is_finite() => Ok(v),
+        _ => Err(Error::msg(format!(
+            "{} should be a positive number, but {}",
+            name, s
+        ))),
+    }
+}
+
+pub(crate) fn print_endpoint(antennas: &Catalog, endpoint: &Endpoint, modifiers: &Modifiers) {
+    print!(
+        "{}",
+        markdown::endpoint(&EndpointSummary::new(antennas, endpoint), modifiers)
+    );
+}
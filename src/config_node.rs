@@ -0,0 +1,160 @@
+//! Parser of KSP ConfigNode files, such as part `.cfg`, `.craft` and `.sfs`.
+
+use anyhow::{Error, Result};
+
+#[derive(Debug, Clone, Default)]
+pub struct Node {
+    pub name: String,
+    pub values: Vec<(String, String)>,
+    pub nodes: Vec<Node>,
+    /// Line where the node starts, 1-origin.
+    pub line: usize,
+}
+
+impl Node {
+    fn new(name: &str, line: usize) -> Node {
+        Node {
+            name: name.to_owned(),
+            line,
+            ..Node::default()
+        }
+    }
+
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn nodes_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.nodes.iter().filter(move |n| n.name == name)
+    }
+}
+
+/// Parses `source` into a root node with an empty name.
+pub fn parse(source: &str) -> Result<Node> {
+    let source = source.trim_start_matches('\u{feff}');
+
+    let mut stack = vec![Node::new("", 1)];
+    let mut pending: Option<String> = None;
+
+    for (i, raw_line) in source.lines().enumerate() {
+        let line_no = i + 1;
+        let line = match raw_line.find("//") {
+            Some(p) => &raw_line[..p],
+            None => raw_line,
+        };
+
+        let mut buf = String::new();
+        for c in line.chars() {
+            match c {
+                '{' => {
+                    let text = buf.trim();
+                    let name = if text.is_empty() {
+                        pending.take().unwrap_or_default()
+                    } else {
+                        pending = None;
+                        text.to_owned()
+                    };
+                    buf.clear();
+                    stack.push(Node::new(&name, line_no));
+                }
+                '}' => {
+                    statement(&mut stack, &mut pending, &buf);
+                    buf.clear();
+                    pending = None;
+
+                    if stack.len() <= 1 {
+                        return Err(Error::msg(format!("line {}: unexpected '}}'", line_no)));
+                    }
+                    let node = stack.pop().unwrap();
+                    stack.last_mut().unwrap().nodes.push(node);
+                }
+                _ => buf.push(c),
+            }
+        }
+        statement(&mut stack, &mut pending, &buf);
+    }
+
+    if stack.len() > 1 {
+        let node = stack.last().unwrap();
+        return Err(Error::msg(format!(
+            "line {}: node '{}' is not closed",
+            node.line, node.name
+        )));
+    }
+
+    Ok(stack.pop().unwrap())
+}
+
+fn statement(stack: &mut [Node], pending: &mut Option<String>, text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+
+    match text.find('=') {
+        Some(p) => {
+            *pending = None;
+            let key = text[..p].trim().to_owned();
+            let value = text[p + 1..].trim().to_owned();
+            stack.last_mut().unwrap().values.push((key, value));
+        }
+        None => *pending = Some(text.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_nodes_and_comments() {
+        let source = "\u{feff}// Part config
+PART
+{
+    name = longAntenna // the part name
+    title = Communotron 16
+    MODULE { name = ModuleDataTransmitter }
+    MODULE
+    {
+        name = ModuleDeployableAntenna
+        // extendAnimationName = deploy
+    }
+}
+";
+        let root = parse(source).unwrap();
+        assert_eq!(root.name, "");
+        assert_eq!(root.nodes.len(), 1);
+
+        let part = &root.nodes[0];
+        assert_eq!(part.name, "PART");
+        assert_eq!(part.line, 3);
+        assert_eq!(part.value("name"), Some("longAntenna"));
+        assert_eq!(part.value("title"), Some("Communotron 16"));
+        assert_eq!(part.value("mass"), None);
+
+        let modules: Vec<&Node> = part.nodes_named("MODULE").collect();
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].value("name"), Some("ModuleDataTransmitter"));
+        assert_eq!(modules[1].line, 8);
+        assert_eq!(modules[1].values.len(), 1);
+    }
+
+    #[test]
+    fn keeps_values_with_equals_sign() {
+        let root = parse("a = b = c\nkey=\n").unwrap();
+        assert_eq!(root.value("a"), Some("b = c"));
+        assert_eq!(root.value("key"), Some(""));
+    }
+
+    #[test]
+    fn rejects_unbalanced_braces() {
+        let e = parse("PART\n{\n}\n}\n").unwrap_err();
+        assert_eq!(e.to_string(), "line 4: unexpected '}'");
+
+        let e = parse("PART\n{\n  MODULE\n  {\n  }\n").unwrap_err();
+        assert_eq!(e.to_string(), "line 2: node 'PART' is not closed");
+    }
+}
@@ -0,0 +1,331 @@
+//! Designer of relay constellations on a circular orbit.
+//!
+//! For `n` relays evenly phased on an orbit of radius `r`, neighbours are
+//! `2 r sin(pi / n)` apart, and the line between them clears the body if
+//! `r cos(pi / n)` is above the occluding radius.
+
+use std::f64::consts::PI;
+
+use anyhow::{Error, Result};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::bodies::Body;
+use crate::calendar::format_duration;
+use crate::catalog::Catalog;
+use crate::cli::{load_system, parse_factor, parse_modifiers, print_endpoint};
+use crate::endpoint::{EndpointBuilder, Role};
+use crate::metric::parse_distance;
+use crate::signal::distance_for_strength;
+use crate::solve::parse_percent;
+use crate::{DEFAULT_TO, INDENT};
+
+pub const NAME: &str = "constellation";
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME)
+        .about("Find the fewest relays on a circular orbit which link to their neighbours")
+        .arg(
+            Arg::with_name("body")
+                .long("body")
+                .takes_value(true)
+                .value_name("BODY")
+                .help("Body of the constellation (default: the home body)"),
+        )
+        .arg(
+            Arg::with_name("antenna")
+                .short("a")
+                .long("antenna")
+                .multiple(true)
+                .takes_value(true)
+                .required(true)
+                .help("Antennas of each relay"),
+        )
+        .arg(
+            Arg::with_name("min-altitude")
+                .long("min-altitude")
+                .takes_value(true)
+                .value_name("DISTANCE")
+                .default_value("0")
+                .help("Lowest altitude of the orbit, e.g. above the atmosphere"),
+        )
+        .arg(
+            Arg::with_name("altitude")
+                .long("altitude")
+                .takes_value(true)
+                .value_name("DISTANCE")
+                .help("Altitude to deploy at (default: the middle of the band)"),
+        )
+        .arg(
+            Arg::with_name("occlusion")
+                .long("occlusion")
+                .takes_value(true)
+                .default_value("1")
+                .help("Occlusion multiplier of the body radius"),
+        )
+        .arg(
+            Arg::with_name("min-strength")
+                .long("min-strength")
+                .takes_value(true)
+                .value_name("PERCENT")
+                .default_value("0")
+                .help("Required strength between neighbours in percent"),
+        )
+        .arg(
+            Arg::with_name("max-count")
+                .long("max-count")
+                .takes_value(true)
+                .default_value("12")
+                .help("Maximum number of relays"),
+        )
+}
+
+/// Orbit radii from the center where `count` relays link to their neighbours.
+#[derive(Debug, Clone, Copy)]
+struct Band {
+    count: usize,
+    min: f64,
+    max: f64,
+}
+
+impl Band {
+    /// Band between the radius where neighbours clear the `occluder` radius,
+    /// and the radius where they are `reach` apart, within `floor` and `soi`.
+    fn new(count: usize, occluder: f64, reach: f64, floor: f64, soi: f64) -> Band {
+        let half = PI / count as f64;
+        Band {
+            count,
+            min: (occluder / half.cos()).max(floor),
+            max: (reach / (2.0 * half.sin())).min(soi),
+        }
+    }
+}
+
+pub fn constellation(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let modifiers = parse_modifiers(matches)?;
+    let system = load_system(matches)?;
+    let body = match matches.value_of("body") {
+        Some(name) => system
+            .get(name)
+            .ok_or_else(|| Error::msg(format!("unknown body '{}'", name)))?,
+        None => system.home(),
+    };
+    if body.mu <= 0.0 {
+        return Err(Error::msg(format!(
+            "gravitational parameter of {} is unknown",
+            body.name
+        )));
+    }
+
+    let relay = EndpointBuilder::new(antennas)
+        .modifiers(modifiers)
+        .role(Some(Role::Relay))
+        .build(matches.values_of("antenna").unwrap_or_default(), DEFAULT_TO)?;
+    let min_strength = parse_percent(matches.value_of("min-strength").unwrap())?;
+    let reach = distance_for_strength(relay.range_to(&relay).max_distance(), min_strength);
+
+    let floor = body.radius + parse_distance(matches.value_of("min-altitude").unwrap())?;
+    let occlusion = parse_factor("occlusion", matches.value_of("occlusion").unwrap())?;
+    let occluder = body.radius * occlusion;
+    let max_count: usize = matches.value_of("max-count").unwrap().parse()?;
+
+    // Two relays 180° apart never see each other through the body, so start at 3.
+    if max_count < 3 {
+        return Err(Error::msg(format!(
+            "max-count should be at least 3, but {}",
+            max_count
+        )));
+    }
+    let bands: Vec<Band> = (3..=max_count)
+        .map(|count| Band::new(count, occluder, reach, floor, body.soi))
+        .collect();
+
+    println!();
+    println!(" Relay:");
+    print_endpoint(antennas, &relay, &modifiers);
+    println!();
+    println!(
+        " Body: {}, radius {}m, SOI {}m",
+        body.name,
+        MetricPrefix(body.radius),
+        MetricPrefix(body.soi)
+    );
+    println!(" Max distance between neighbours: {}m", MetricPrefix(reach));
+    println!();
+
+    println!(" | Count | Min altitude | Max altitude |");
+    println!(" |------:|-------------:|-------------:|");
+    for b in &bands {
+        let (lo, hi) = if b.min < b.max {
+            (
+                format!("{}m", MetricPrefix(b.min - body.radius)),
+                format!("{}m", MetricPrefix(b.max - body.radius)),
+            )
+        } else {
+            ("NA".to_owned(), "NA".to_owned())
+        };
+        println!(" | {:>5} | {:>12} | {:>12} |", b.count, lo, hi);
+    }
+    println!();
+
+    let best = match bands.iter().find(|b| b.min < b.max) {
+        Some(b) => *b,
+        None => {
+            return Err(Error::msg(format!(
+                "no constellation of up to {} relays links around {}",
+                max_count, body.name
+            )))
+        }
+    };
+    println!(
+        " Minimum: {} relays between {}m and {}m",
+        best.count,
+        MetricPrefix(best.min - body.radius),
+        MetricPrefix(best.max - body.radius)
+    );
+
+    let radius = match matches.value_of("altitude") {
+        Some(a) => body.radius + parse_distance(a)?,
+        None => (best.min + best.max) / 2.0,
+    };
+    if radius < best.min || radius > best.max {
+        eprintln!(
+            "Warning: {}m is out of the band of {} relays",
+            MetricPrefix(radius - body.radius),
+            best.count
+        );
+    }
+    print_deployment(body, best.count, radius, floor);
+
+    Ok(())
+}
+
+/// Orbit of the carrier which releases one relay per orbit.
+#[derive(Debug, Clone, Copy)]
+struct Resonance {
+    /// Period of the carrier over the period of the relays, as `num / den`.
+    num: f64,
+    den: f64,
+    period: f64,
+    pe: f64,
+    ap: f64,
+}
+
+/// Resonant orbits to release `count` relays evenly, above and below the target orbit.
+fn resonances(body: &Body, count: usize, radius: f64) -> Vec<Resonance> {
+    let period = orbital_period(body, radius);
+    let n = count as f64;
+    [(n + 1.0, n), (n - 1.0, n)]
+        .iter()
+        .map(|&(num, den)| {
+            let sma = radius * (num / den).powf(2.0 / 3.0);
+            // The carrier touches the target orbit at one apsis.
+            let other = 2.0 * sma - radius;
+            let (pe, ap) = if other > radius {
+                (radius, other)
+            } else {
+                (other, radius)
+            };
+            Resonance {
+                num,
+                den,
+                period: period * num / den,
+                pe,
+                ap,
+            }
+        })
+        .collect()
+}
+
+fn print_deployment(body: &Body, count: usize, radius: f64, floor: f64) {
+    println!(
+        " Deployment at {}m, period {}:",
+        MetricPrefix(radius - body.radius),
+        format_duration(orbital_period(body, radius))
+    );
+
+    for r in resonances(body, count, radius) {
+        print!(
+            " {}{}/{} resonant orbit: period {}, Pe {}m, Ap {}m",
+            INDENT,
+            r.num,
+            r.den,
+            format_duration(r.period),
+            MetricPrefix(r.pe - body.radius),
+            MetricPrefix(r.ap - body.radius)
+        );
+        if r.pe < floor {
+            print!(" (too low)");
+        }
+        println!();
+    }
+    println!();
+}
+
+fn orbital_period(body: &Body, radius: f64) -> f64 {
+    2.0 * PI * (radius.powi(3) / body.mu).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bodies::System;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * b.abs()
+    }
+
+    #[test]
+    fn band_geometry() {
+        let b = Band::new(3, 600e3, 1e6, 0.0, f64::INFINITY);
+        assert!(close(b.min, 1.2e6));
+        assert!(close(b.max, 1e6 / 3f64.sqrt()));
+
+        let b = Band::new(4, 600e3, 1e6, 0.0, f64::INFINITY);
+        assert!(close(b.min, 600e3 * 2f64.sqrt()));
+        assert!(close(b.max, 1e6 / 2f64.sqrt()));
+
+        // Neighbours are `reach` apart at the top of the band, and graze the occluder at its bottom.
+        let b = Band::new(6, 600e3, 2e6, 0.0, f64::INFINITY);
+        assert!(close(2.0 * b.max * (PI / 6.0).sin(), 2e6));
+        assert!(close(b.min * (PI / 6.0).cos(), 600e3));
+    }
+
+    #[test]
+    fn band_is_clamped() {
+        let b = Band::new(3, 600e3, 1e9, 2e6, 84e6);
+        assert_eq!(b.min, 2e6);
+        assert_eq!(b.max, 84e6);
+
+        let b = Band::new(3, 600e3, 1e6, 0.0, f64::INFINITY);
+        assert!(b.min > b.max);
+    }
+
+    #[test]
+    fn resonant_orbits() {
+        let system = System::stock();
+        let kerbin = system.get("Kerbin").unwrap();
+        let radius = kerbin.radius + 1e6;
+        let period = orbital_period(kerbin, radius);
+
+        let r = resonances(kerbin, 3, radius);
+        assert_eq!(r.len(), 2);
+
+        assert_eq!((r[0].num, r[0].den), (4.0, 3.0));
+        assert_eq!(r[0].pe, radius);
+        assert!(r[0].ap > radius);
+        assert!(close(r[0].period, period * 4.0 / 3.0));
+
+        assert_eq!((r[1].num, r[1].den), (2.0, 3.0));
+        assert_eq!(r[1].ap, radius);
+        assert!(r[1].pe < radius);
+        assert!(close(r[1].period, period * 2.0 / 3.0));
+
+        // The period of each carrier orbit follows from its semi-major axis.
+        for o in &r {
+            assert!(close(orbital_period(kerbin, (o.pe + o.ap) / 2.0), o.period));
+        }
+    }
+}
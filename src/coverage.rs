@@ -0,0 +1,463 @@
+//! Line of sight and strength between the relays of a constellation and a
+//! point on the surface of their body.
+
+use anyhow::{Error, Result};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::calendar::format_duration;
+use crate::catalog::Catalog;
+use crate::cli::{load_system, parse_factor, parse_modifiers};
+use crate::endpoint::{EndpointBuilder, Role};
+use crate::metric::parse_distance;
+use crate::occlusion::{
+    distance, line_of_sight, surface_line_of_sight, Constellation, SurfacePoint,
+};
+use crate::render::format_strength;
+use crate::signal::strength_at;
+use crate::solve::parse_percent;
+use crate::{DEFAULT_FROM, DEFAULT_TO};
+
+pub const NAME: &str = "coverage";
+
+/// Latitude and longitude of KSC.
+const KSC: (&str, &str) = ("-0.0972", "-74.5577");
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME)
+        .about("Line of sight between a relay constellation and a surface point")
+        .arg(
+            Arg::with_name("body")
+                .long("body")
+                .takes_value(true)
+                .value_name("BODY")
+                .help("Body of the constellation (default: the home body)"),
+        )
+        .arg(
+            Arg::with_name("count")
+                .short("n")
+                .long("count")
+                .takes_value(true)
+                .required(true)
+                .help("Number of relays"),
+        )
+        .arg(
+            Arg::with_name("altitude")
+                .long("altitude")
+                .takes_value(true)
+                .required(true)
+                .value_name("DISTANCE")
+                .help("Altitude of the circular orbit of the relays"),
+        )
+        .arg(
+            Arg::with_name("inclination")
+                .long("inclination")
+                .takes_value(true)
+                .value_name("DEGREES")
+                .default_value("0")
+                .help("Inclination of the orbit"),
+        )
+        .arg(
+            Arg::with_name("phase")
+                .long("phase")
+                .takes_value(true)
+                .value_name("DEGREES")
+                .help("Phase between neighbouring relays (default: 360 / count)"),
+        )
+        .arg(
+            Arg::with_name("relay")
+                .short("r")
+                .long("relay")
+                .multiple(true)
+                .takes_value(true)
+                .help("Antennas of each relay"),
+        )
+        .arg(
+            Arg::with_name("ground")
+                .short("g")
+                .long("ground")
+                .multiple(true)
+                .takes_value(true)
+                .default_value(DEFAULT_FROM)
+                .help("Antennas of the surface point"),
+        )
+        .arg(
+            Arg::with_name("lat")
+                .long("lat")
+                .takes_value(true)
+                .value_name("DEGREES")
+                .default_value(KSC.0)
+                .allow_hyphen_values(true)
+                .help("Latitude of the surface point (default: KSC)"),
+        )
+        .arg(
+            Arg::with_name("lon")
+                .long("lon")
+                .takes_value(true)
+                .value_name("DEGREES")
+                .default_value(KSC.1)
+                .allow_hyphen_values(true)
+                .help("Longitude of the surface point (default: KSC)"),
+        )
+        .arg(
+            Arg::with_name("occlusion")
+                .long("occlusion")
+                .takes_value(true)
+                .default_value("1")
+                .help("Occlusion multiplier of the body radius"),
+        )
+        .arg(
+            Arg::with_name("min-strength")
+                .long("min-strength")
+                .takes_value(true)
+                .value_name("PERCENT")
+                .default_value("0")
+                .help("Required strength in percent (e.g. 50 or 50%)"),
+        )
+        .arg(
+            Arg::with_name("samples")
+                .long("samples")
+                .takes_value(true)
+                .default_value("10000")
+                .help("Number of time steps over 10 rotations of the body or 10 orbits"),
+        )
+}
+
+/// Time steps of a relay seen from the surface point.
+#[derive(Debug, Clone, Default)]
+struct RelayStats {
+    visible: usize,
+    /// Steps in line of sight and above the required strength.
+    linked: usize,
+    best: Option<f64>,
+}
+
+/// Coverage of a surface point by a constellation, in time steps.
+#[derive(Debug, Clone)]
+struct Coverage {
+    relays: Vec<RelayStats>,
+    /// Steps linked to any relay.
+    covered: usize,
+    /// Longest run of steps without a link.
+    longest_gap: usize,
+}
+
+/// Samples the line of sight and the strength from the surface point to each relay
+/// at `samples` steps from `t = 0`.
+fn sample_coverage(
+    constellation: &Constellation,
+    point: &SurfacePoint,
+    occluder: f64,
+    ground_range: f64,
+    threshold: f64,
+    step: f64,
+    samples: usize,
+) -> Coverage {
+    let mut coverage = Coverage {
+        relays: vec![RelayStats::default(); constellation.count],
+        covered: 0,
+        longest_gap: 0,
+    };
+    let mut gap = 0;
+    for s in 0..samples {
+        let t = s as f64 * step;
+        let g = point.position(t);
+        let mut any = false;
+        for (i, st) in coverage.relays.iter_mut().enumerate() {
+            let p = constellation.position(i, t);
+            if !surface_line_of_sight(g, p, occluder) {
+                continue;
+            }
+            st.visible += 1;
+
+            if let Some(v) = strength_at(ground_range, distance(g, p)) {
+                st.best = Some(st.best.map_or(v, |b| b.max(v)));
+                if v >= threshold {
+                    st.linked += 1;
+                    any = true;
+                }
+            }
+        }
+
+        if any {
+            coverage.covered += 1;
+            gap = 0;
+        } else {
+            gap += 1;
+            coverage.longest_gap = coverage.longest_gap.max(gap);
+        }
+    }
+    coverage
+}
+
+pub fn coverage(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let modifiers = parse_modifiers(matches)?;
+    let system = load_system(matches)?;
+    let body = match matches.value_of("body") {
+        Some(name) => system
+            .get(name)
+            .ok_or_else(|| Error::msg(format!("unknown body '{}'", name)))?,
+        None => system.home(),
+    };
+    if body.mu <= 0.0 {
+        return Err(Error::msg(format!(
+            "gravitational parameter of {} is unknown",
+            body.name
+        )));
+    }
+
+    let count: usize = matches.value_of("count").unwrap().parse()?;
+    if count == 0 {
+        return Err(Error::msg("count should be positive"));
+    }
+    let altitude = parse_distance(matches.value_of("altitude").unwrap())?;
+    let inclination = parse_degrees(matches, "inclination")?;
+    let spacing = match matches.value_of("phase") {
+        Some(_) => parse_degrees(matches, "phase")?,
+        None => 2.0 * std::f64::consts::PI / count as f64,
+    };
+    let occlusion = parse_factor("occlusion", matches.value_of("occlusion").unwrap())?;
+    let occluder = body.radius * occlusion;
+    let threshold = parse_percent(matches.value_of("min-strength").unwrap())?;
+    let samples = matches
+        .value_of("samples")
+        .unwrap()
+        .parse::<usize>()?
+        .max(1);
+
+    let relay = EndpointBuilder::new(antennas)
+        .modifiers(modifiers)
+        .role(Some(Role::Relay))
+        .build(matches.values_of("relay").unwrap_or_default(), DEFAULT_TO)?;
+    let ground = EndpointBuilder::new(antennas).modifiers(modifiers).build(
+        matches.values_of("ground").unwrap_or_default(),
+        DEFAULT_FROM,
+    )?;
+
+    let constellation = Constellation::new(body, count, altitude, inclination, spacing);
+    let point = SurfacePoint::new(
+        body,
+        parse_degrees(matches, "lat")?,
+        parse_degrees(matches, "lon")?,
+    );
+
+    println!();
+    println!(
+        " Constellation: {} relays at {}m around {}, inclination {:.1}°, spacing {:.1}°",
+        count,
+        MetricPrefix(altitude),
+        body.name,
+        inclination.to_degrees(),
+        spacing.to_degrees()
+    );
+    println!(
+        " Orbital period: {}",
+        format_duration(constellation.period())
+    );
+    println!();
+
+    // Relays on the same circular orbit keep their distances.
+    let relay_range = relay.range_to(&relay).max_distance();
+    println!(" |  Link  | Distance | Line of sight | Strength |");
+    println!(" |:-------|---------:|:-------------:|---------:|");
+    for (a, b) in constellation.neighbours() {
+        let (pa, pb) = (
+            constellation.position(a, 0.0),
+            constellation.position(b, 0.0),
+        );
+        let d = distance(pa, pb);
+        let visible = line_of_sight(pa, pb, occluder);
+        println!(
+            " | {:<6} | {:>8} | {:^13} | {:>8} |",
+            format!("{} - {}", a + 1, b + 1),
+            format!("{}m", MetricPrefix(d)),
+            if visible { "yes" } else { "NO" },
+            format_strength(if visible {
+                strength_at(relay_range, d)
+            } else {
+                None
+            }),
+        );
+    }
+    println!();
+
+    let duration = 10.0 * constellation.period().max(body.rotation_period);
+    let step = duration / samples as f64;
+    let ground_range = ground.range_to(&relay).max_distance();
+    let coverage = sample_coverage(
+        &constellation,
+        &point,
+        occluder,
+        ground_range,
+        threshold,
+        step,
+        samples,
+    );
+
+    let total = samples as f64;
+    println!(
+        " Surface point: {}°, {}° on {}",
+        matches.value_of("lat").unwrap(),
+        matches.value_of("lon").unwrap(),
+        body.name
+    );
+    println!(" Max distance: {}m", MetricPrefix(ground_range));
+    println!();
+    println!(" | Relay | Line of sight |  Linked  | Best strength |");
+    println!(" |:------|--------------:|---------:|--------------:|");
+    for (i, st) in coverage.relays.iter().enumerate() {
+        println!(
+            " | {:<5} | {:>13} | {:>8} | {:>13} |",
+            i + 1,
+            format_strength(Some(st.visible as f64 / total)),
+            format_strength(Some(st.linked as f64 / total)),
+            format_strength(st.best),
+        );
+    }
+    println!();
+    println!(
+        " Linked to any relay: {}",
+        format_strength(Some(coverage.covered as f64 / total))
+    );
+    if coverage.longest_gap > 0 {
+        println!(
+            " Longest time without link: {}",
+            format_duration(coverage.longest_gap as f64 * step)
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Parses an arg in degrees into radians.
+fn parse_degrees(matches: &ArgMatches, name: &str) -> Result<f64> {
+    let s = matches.value_of(name).unwrap_or("0");
+    s.trim()
+        .parse::<f64>()
+        .map(f64::to_radians)
+        .map_err(|_| Error::msg(format!("invalid {}: {}", name, s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::f64::consts::PI;
+
+    const RADIUS: f64 = 600e3;
+    const PERIOD: f64 = 1000.0;
+    const SAMPLES: usize = 36000;
+
+    /// Equatorial relays at `orbit` radii around a body which does not rotate.
+    fn constellation(count: usize, orbit: f64) -> Constellation {
+        Constellation {
+            count,
+            radius: orbit * RADIUS,
+            inclination: 0.0,
+            spacing: 2.0 * PI / count as f64,
+            mean_motion: 2.0 * PI / PERIOD,
+        }
+    }
+
+    fn point(latitude: f64) -> SurfacePoint {
+        SurfacePoint {
+            latitude: latitude.to_radians(),
+            longitude: 0.0,
+            radius: RADIUS,
+            rotation: 0.0,
+        }
+    }
+
+    fn sample(
+        count: usize,
+        orbit: f64,
+        latitude: f64,
+        ground_range: f64,
+        threshold: f64,
+    ) -> Coverage {
+        let step = 10.0 * PERIOD / SAMPLES as f64;
+        sample_coverage(
+            &constellation(count, orbit),
+            &point(latitude),
+            RADIUS,
+            ground_range,
+            threshold,
+            step,
+            SAMPLES,
+        )
+    }
+
+    fn fraction(steps: usize) -> f64 {
+        steps as f64 / SAMPLES as f64
+    }
+
+    /// Fraction of an orbit at `orbit` radii above the horizon of an equatorial point.
+    fn horizon(orbit: f64) -> f64 {
+        (1.0 / orbit).acos() / PI
+    }
+
+    #[test]
+    fn one_relay_over_the_equator() {
+        // Above the horizon within acos(1 / 2) = 60° of the point, and within
+        // 1.5 radii where cos θ >= 0.6875.
+        let c = sample(1, 2.0, -0.0972, 1.5 * RADIUS, 0.0);
+        let st = &c.relays[0];
+
+        assert!(
+            (fraction(st.visible) - horizon(2.0)).abs() < 1e-3,
+            "{}",
+            fraction(st.visible)
+        );
+        let linked = 0.6875f64.acos() / PI;
+        assert!(
+            (fraction(st.linked) - linked).abs() < 1e-3,
+            "{}",
+            fraction(st.linked)
+        );
+        assert_eq!(c.covered, st.linked);
+        assert!(st.best.unwrap() > 0.25);
+
+        // Without link for the rest of each of the 10 orbits.
+        let gap = fraction(c.longest_gap) * 10.0;
+        assert!((gap - (1.0 - linked)).abs() < 1e-2, "{}", gap);
+    }
+
+    #[test]
+    fn three_relays_cover_ksc_latitude() {
+        let c = sample(3, 3.0, -0.0972, 10.0 * RADIUS, 0.0);
+
+        assert_eq!(c.covered, SAMPLES);
+        assert_eq!(c.longest_gap, 0);
+        for st in &c.relays {
+            assert!((fraction(st.visible) - horizon(3.0)).abs() < 1e-3);
+            assert_eq!(st.linked, st.visible);
+        }
+    }
+
+    #[test]
+    fn threshold_limits_links() {
+        let all = sample(3, 3.0, -0.0972, 10.0 * RADIUS, 0.0);
+        let strong = sample(3, 3.0, -0.0972, 10.0 * RADIUS, 0.85);
+
+        for (a, s) in all.relays.iter().zip(&strong.relays) {
+            assert_eq!(a.visible, s.visible);
+            assert!(s.linked > 0 && s.linked < a.visible);
+        }
+        assert!(strong.covered < SAMPLES);
+        assert!(strong.longest_gap > 0);
+    }
+
+    #[test]
+    fn pole_never_sees_equatorial_relays() {
+        let c = sample(3, 3.0, 90.0, 10.0 * RADIUS, 0.0);
+
+        assert_eq!(c.covered, 0);
+        assert_eq!(c.longest_gap, SAMPLES);
+        for st in &c.relays {
+            assert_eq!(st.visible, 0);
+            assert_eq!(st.best, None);
+        }
+    }
+}
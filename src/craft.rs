@@ -0,0 +1,38 @@
+//! Vessels designed in the VAB or SPH (`.craft` files).
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+
+use crate::catalog::Catalog;
+use crate::config_node::{self, Node};
+use crate::parts::{collect_antennas, PartAntennas};
+
+pub struct Craft {
+    pub name: String,
+    parts: Vec<Node>,
+}
+
+impl Craft {
+    pub fn load(path: &Path) -> Result<Craft> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?;
+        let root = config_node::parse(&source)
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?;
+
+        let name = match root.value("ship") {
+            Some(n) => n.to_owned(),
+            None => path.display().to_string(),
+        };
+
+        Ok(Craft {
+            name,
+            parts: root.nodes_named("PART").cloned().collect(),
+        })
+    }
+
+    pub fn antennas(&self, catalog: &Catalog) -> PartAntennas {
+        collect_antennas(catalog, self.parts.iter())
+    }
+}
@@ -0,0 +1,350 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+
+use ksp_commnet_calculator_core::antenna::Antenna;
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+
+use crate::catalog::Catalog;
+use crate::suggest::suggest_antennas;
+use crate::INDENT;
+
+/// Role of a vessel in a link.
+///
+/// Only relay antennas contribute when a vessel relays the signal,
+/// while every antenna contributes to a direct link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Relay,
+    Direct,
+}
+
+impl FromStr for Role {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Role> {
+        match s {
+            "relay" => Ok(Role::Relay),
+            "direct" => Ok(Role::Direct),
+            _ => Err(Error::msg(format!(
+                "role should be 'relay' or 'direct', but {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Range modifiers of the CommNet difficulty settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Modifiers {
+    pub range: f64,
+    pub dsn: f64,
+}
+
+impl Modifiers {
+    pub fn is_default(&self) -> bool {
+        *self == Modifiers::default()
+    }
+
+    /// Factor applied to the power of `antenna`: the DSN modifier for ground stations,
+    /// and the range modifier for the others.
+    pub fn factor(&self, antennas: &Catalog, antenna: &Antenna) -> f64 {
+        if is_ground_station(antennas, antenna) {
+            self.dsn
+        } else {
+            self.range
+        }
+    }
+
+    pub fn apply(&self, antennas: &Catalog, antenna: &Antenna) -> Antenna {
+        let mut a = antenna.clone();
+        a.power *= self.factor(antennas, antenna);
+        a
+    }
+}
+
+impl Default for Modifiers {
+    fn default() -> Self {
+        Modifiers {
+            range: 1.0,
+            dsn: 1.0,
+        }
+    }
+}
+
+pub struct EndpointBuilder<'a> {
+    antennas: &'a Catalog,
+    lenient: bool,
+    role: Option<Role>,
+    modifiers: Modifiers,
+}
+
+impl<'a> EndpointBuilder<'a> {
+    pub fn new(antennas: &'a Catalog) -> Self {
+        EndpointBuilder {
+            antennas,
+            lenient: false,
+            role: None,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    pub fn role(mut self, role: Option<Role>) -> Self {
+        self.role = role;
+        self
+    }
+
+    pub fn modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn build<'s>(
+        &self,
+        antenna_strs: impl Iterator<Item = &'s str>,
+        default: &str,
+    ) -> Result<Endpoint> {
+        let mut endpoint = Endpoint::new();
+        let mut specified = false;
+        for antenna_str in antenna_strs {
+            specified = true;
+
+            let (count, antenna_name) = split_antenna_arg(antenna_str)?;
+            let antenna = match self.antennas.get(antenna_name) {
+                Some(a) => a,
+                None => {
+                    let msg = unknown_antenna_message(self.antennas, antenna_name);
+                    if self.lenient {
+                        eprintln!("Warning: skipped {}", msg);
+                        continue;
+                    }
+                    return Err(Error::msg(msg));
+                }
+            };
+
+            if self.role == Some(Role::Relay) && !is_relay(self.antennas, antenna) {
+                eprintln!(
+                    "Warning: '{}' is not a relay antenna, ignored for relay endpoint",
+                    antenna.name
+                );
+                continue;
+            }
+
+            endpoint.add_antenna(self.modifiers.apply(self.antennas, antenna), count);
+        }
+
+        if endpoint.is_empty() {
+            if specified && !self.lenient {
+                return Err(Error::msg("endpoint has no usable antenna"));
+            }
+
+            let a = self
+                .antennas
+                .get(default)
+                .expect("Default endpoint antenna not exists");
+            endpoint.add_antenna(self.modifiers.apply(self.antennas, a), 1);
+        }
+
+        Ok(endpoint)
+    }
+}
+
+/// Antennas of an endpoint being edited, as (name, count) in the order of addition.
+#[derive(Debug, Clone, Default)]
+pub struct AntennaSet {
+    pub antennas: Vec<(String, usize)>,
+}
+
+impl AntennaSet {
+    /// Antenna specifiers for `EndpointBuilder::build`, count first like `2:HG-5`.
+    pub fn specs(&self) -> Vec<String> {
+        self.antennas
+            .iter()
+            .map(|(n, c)| format!("{}:{}", c, n))
+            .collect()
+    }
+
+    pub fn add(&mut self, name: &str, count: usize) {
+        match self.antennas.iter_mut().find(|(n, _)| n == name) {
+            Some((_, c)) => *c += count,
+            None => self.antennas.push((name.to_owned(), count)),
+        }
+    }
+
+    /// Removes one of the antenna, and returns false if there is none.
+    pub fn remove_one(&mut self, name: &str) -> bool {
+        match self.antennas.iter().position(|(n, _)| n == name) {
+            Some(i) => {
+                self.antennas[i].1 -= 1;
+                if self.antennas[i].1 == 0 {
+                    self.antennas.remove(i);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes all of the antenna, and returns false if there is none.
+    pub fn remove_all(&mut self, name: &str) -> bool {
+        let before = self.antennas.len();
+        self.antennas.retain(|(n, _)| n != name);
+        self.antennas.len() != before
+    }
+
+    pub fn clear(&mut self) {
+        self.antennas.clear();
+    }
+}
+
+pub fn unknown_antenna_message(antennas: &Catalog, antenna_name: &str) -> String {
+    let mut msg = format!("unknown antenna '{}'", antenna_name);
+
+    let suggestions = suggest_antennas(antennas, antenna_name);
+    if !suggestions.is_empty() {
+        msg.push_str("; did you mean:");
+        for s in suggestions {
+            msg.push('\n');
+            msg.push_str(INDENT);
+            msg.push_str(&s);
+        }
+    }
+
+    msg
+}
+
+pub fn is_ground_station_endpoint(antennas: &Catalog, endpoint: &Endpoint) -> bool {
+    let mut ground = false;
+    for (a, _) in endpoint.antenna_counts() {
+        ground |= is_ground_station(antennas, a);
+    }
+    ground
+}
+
+pub fn is_ground_station(antennas: &Catalog, antenna: &Antenna) -> bool {
+    antennas.is_ground_station(antenna)
+}
+
+pub fn is_relay(antennas: &Catalog, antenna: &Antenna) -> bool {
+    antenna.is_relay || is_ground_station(antennas, antenna)
+}
+
+/// Splits an antenna specifier into the count and the name.
+///
+/// The count comes first, like `2:HG-5`, as in the error message of the
+/// original parser. The original order `HG-5:2` is still accepted. If both
+/// sides are numbers, the first one is the count.
+pub fn split_antenna_arg(s: &str) -> Result<(usize, &str)> {
+    let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+
+    match parts.len() {
+        1 => Ok((1, parts[0])),
+        2 => match (parts[0].parse(), parts[1].parse()) {
+            (Ok(n), _) => Ok((n, parts[1])),
+            (Err(_), Ok(n)) => Ok((n, parts[0])),
+            (Err(e), Err(_)) => Err(Error::msg(format!(
+                "invalid number of antenna in '{}': {}",
+                s, e
+            ))),
+        },
+        _ => Err(Error::msg(format!(
+            "antenna specifier should be [<NUMBER_OF_ANTENNA>:]<ANTENNA_NAME>, but {}",
+            s
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::catalog::AntennaDef;
+    use crate::{DEFAULT_FROM, DEFAULT_TO};
+
+    fn ground_station_def(name: &str, ground_station: bool) -> AntennaDef {
+        AntennaDef {
+            name: name.to_owned(),
+            aliases: Vec::new(),
+            power: 1.0e9,
+            combinable: false,
+            combinable_exponent: 0.75,
+            relay: false,
+            ground_station: Some(ground_station),
+            mass: None,
+            cost: None,
+            packet_size: None,
+            packet_interval: None,
+            packet_cost: None,
+        }
+    }
+
+    #[test]
+    fn splits_count_first_and_name_first() {
+        assert_eq!(split_antenna_arg("HG-5").unwrap(), (1, "HG-5"));
+        assert_eq!(split_antenna_arg("2:HG-5").unwrap(), (2, "HG-5"));
+        assert_eq!(split_antenna_arg(" 2 : HG-5 ").unwrap(), (2, "HG-5"));
+        assert_eq!(split_antenna_arg("HG-5:2").unwrap(), (2, "HG-5"));
+        assert_eq!(
+            split_antenna_arg("Communotron 88-88:3").unwrap(),
+            (3, "Communotron 88-88")
+        );
+    }
+
+    #[test]
+    fn splits_ambiguous_specifiers() {
+        // Both sides are numbers: the count comes first.
+        assert_eq!(split_antenna_arg("2:3").unwrap(), (2, "3"));
+
+        assert!(split_antenna_arg("HG-5:RA-2").is_err());
+        assert!(split_antenna_arg("-1:HG-5").is_err());
+        assert!(split_antenna_arg("1:2:HG-5").is_err());
+    }
+
+    #[test]
+    fn modifiers_scale_max_distance() {
+        let antennas = Catalog::new();
+        let builder = EndpointBuilder::new(&antennas).modifiers(Modifiers {
+            range: 2.0,
+            dsn: 3.0,
+        });
+        let from = builder
+            .build(vec!["DSN Lv.3"].into_iter(), DEFAULT_FROM)
+            .unwrap();
+        let to = builder.build(vec!["HG-5"].into_iter(), DEFAULT_TO).unwrap();
+
+        // sqrt((250G * 3) * (5M * 2))
+        let expected = (750.0e9_f64 * 10.0e6).sqrt();
+        let actual = from.range_to(&to).max_distance();
+        assert!((actual - expected).abs() / expected < 1e-9, "{}", actual);
+    }
+
+    #[test]
+    fn ground_stations_from_catalog() {
+        let mut antennas = Catalog::new();
+        antennas
+            .add(ground_station_def("Mission Control", true))
+            .unwrap();
+        antennas.add(ground_station_def("DSN Dish", false)).unwrap();
+
+        let modifiers = Modifiers {
+            range: 2.0,
+            dsn: 3.0,
+        };
+        for (name, ground, factor) in &[
+            ("DSN Lv.1", true, 3.0),
+            ("Mission Control", true, 3.0),
+            ("DSN Dish", false, 2.0),
+            ("Communotron 16", false, 2.0),
+        ] {
+            let a = antennas.get(name).unwrap();
+            assert_eq!(is_ground_station(&antennas, a), *ground, "{}", name);
+            assert_eq!(is_relay(&antennas, a), *ground, "{}", name);
+            assert_eq!(modifiers.factor(&antennas, a), *factor, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Positions of bodies and vessels over time.
+//!
+//! Orbits are Keplerian and propagated in a single plane, ignoring
+//! inclinations. Positions are relative to the star.
+
+use std::f64::consts::PI;
+
+use anyhow::{Error, Result};
+
+use crate::bodies::{Body, System};
+
+/// Keplerian orbit around a body.
+#[derive(Debug, Clone)]
+pub struct Elements {
+    pub parent: String,
+    pub sma: f64,
+    pub eccentricity: f64,
+    /// Longitude of periapsis in radians.
+    pub longitude_of_periapsis: f64,
+    /// Mean anomaly at the epoch in radians.
+    pub mean_anomaly: f64,
+}
+
+impl Elements {
+    /// Orbit of a body around its parent, or `None` for the star.
+    pub fn of_body(body: &Body) -> Option<Elements> {
+        Some(Elements {
+            parent: body.parent.clone()?,
+            sma: body.sma,
+            eccentricity: body.eccentricity,
+            longitude_of_periapsis: (body.lan + body.arg_periapsis).to_radians(),
+            mean_anomaly: body.mean_anomaly,
+        })
+    }
+
+    /// Position relative to the parent at `t` seconds after the epoch.
+    fn position(&self, mu: f64, t: f64) -> (f64, f64) {
+        let n = (mu / self.sma.powi(3)).sqrt();
+        let m = (self.mean_anomaly + n * t).rem_euclid(2.0 * PI);
+        let e = self.eccentricity;
+
+        let mut ea = if e < 0.8 { m } else { PI };
+        for _ in 0..50 {
+            let delta = (ea - e * ea.sin() - m) / (1.0 - e * ea.cos());
+            ea -= delta;
+            if delta.abs() < 1e-12 {
+                break;
+            }
+        }
+
+        // Position in the orbital plane, with periapsis on the x axis.
+        let x = self.sma * (ea.cos() - e);
+        let y = self.sma * (1.0 - e * e).sqrt() * ea.sin();
+
+        let (sin, cos) = self.longitude_of_periapsis.sin_cos();
+        (x * cos - y * sin, x * sin + y * cos)
+    }
+}
+
+/// Position of an orbit relative to the star at `t`, including the motion of its parents.
+pub fn position(system: &System, elements: &Elements, t: f64) -> Result<(f64, f64)> {
+    let parent = system
+        .get(&elements.parent)
+        .ok_or_else(|| Error::msg(format!("unknown body '{}'", elements.parent)))?;
+    if parent.mu <= 0.0 {
+        return Err(Error::msg(format!(
+            "gravitational parameter of {} is unknown",
+            parent.name
+        )));
+    }
+
+    let (x, y) = elements.position(parent.mu, t);
+    let (px, py) = body_position(system, parent, t)?;
+    Ok((x + px, y + py))
+}
+
+/// Position of the center of a body relative to the star at `t`.
+pub fn body_position(system: &System, body: &Body, t: f64) -> Result<(f64, f64)> {
+    match Elements::of_body(body) {
+        Some(elements) => position(system, &elements, t),
+        None => Ok((0.0, 0.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(mu: f64, sma: f64) -> f64 {
+        2.0 * PI * (sma.powi(3) / mu).sqrt()
+    }
+
+    #[test]
+    fn returns_after_a_period() {
+        let system = System::stock();
+        let duna = system.get("Duna").unwrap();
+        let t = period(system.get("Kerbol").unwrap().mu, duna.sma);
+
+        let (x0, y0) = body_position(&system, duna, 0.0).unwrap();
+        let (x1, y1) = body_position(&system, duna, t).unwrap();
+        assert!((x1 - x0).hypot(y1 - y0) < 1e-6 * duna.sma);
+    }
+
+    #[test]
+    fn stays_between_periapsis_and_apoapsis() {
+        let system = System::stock();
+        let duna = system.get("Duna").unwrap();
+        let t = period(system.get("Kerbol").unwrap().mu, duna.sma);
+
+        for i in 0..100 {
+            let (x, y) = body_position(&system, duna, t * i as f64 / 100.0).unwrap();
+            let r = x.hypot(y);
+            assert!(r >= duna.sma * (1.0 - duna.eccentricity) * (1.0 - 1e-9));
+            assert!(r <= duna.sma * (1.0 + duna.eccentricity) * (1.0 + 1e-9));
+        }
+    }
+
+    #[test]
+    fn kerbin_duna_distance_over_a_synodic_period() {
+        let system = System::stock();
+        let mu = system.get("Kerbol").unwrap().mu;
+        let kerbin = system.get("Kerbin").unwrap();
+        let duna = system.get("Duna").unwrap();
+        let synodic = 1.0 / (1.0 / period(mu, kerbin.sma) - 1.0 / period(mu, duna.sma));
+
+        let lower = duna.sma * (1.0 - duna.eccentricity) - kerbin.sma;
+        let upper = duna.sma * (1.0 + duna.eccentricity) + kerbin.sma;
+        let (mut closest, mut farthest) = (f64::INFINITY, 0.0_f64);
+        for i in 0..1000 {
+            let t = synodic * i as f64 / 1000.0;
+            let (x1, y1) = body_position(&system, kerbin, t).unwrap();
+            let (x2, y2) = body_position(&system, duna, t).unwrap();
+            let d = (x1 - x2).hypot(y1 - y2);
+            assert!(d >= lower && d <= upper, "{} at {}", d, t);
+            closest = closest.min(d);
+            farthest = farthest.max(d);
+        }
+
+        // Both the conjunction and the opposition happen within a synodic period.
+        assert!(closest <= (duna.sma * (1.0 + duna.eccentricity) - kerbin.sma) * 1.001);
+        assert!(farthest >= (duna.sma * (1.0 - duna.eccentricity) + kerbin.sma) * 0.999);
+    }
+
+    #[test]
+    fn moons_move_with_their_parent() {
+        let system = System::stock();
+        let kerbin = system.get("Kerbin").unwrap();
+        let mun = system.get("Mun").unwrap();
+
+        for &t in &[0.0, 1.0e6, 1.0e7] {
+            let (x1, y1) = body_position(&system, kerbin, t).unwrap();
+            let (x2, y2) = body_position(&system, mun, t).unwrap();
+            assert!(((x1 - x2).hypot(y1 - y2) - mun.sma).abs() < 1.0);
+        }
+    }
+}
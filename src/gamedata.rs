@@ -0,0 +1,732 @@
+//! Import of antennas from part configs in a KSP GameData directory.
+//!
+//! Every `PART` with a `ModuleDataTransmitter` module becomes an antenna,
+//! except the `INTERNAL` transmitters of command pods and probe cores, which
+//! are the `Command Module` antenna. Simple ModuleManager patches (`@PART[name]`
+//! editing values and `@MODULE[ModuleDataTransmitter]`) are applied in the
+//! order of the ModuleManager passes. Other patches are skipped with a warning.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Error, Result};
+
+use crate::catalog::{AntennaDef, DEFAULT_COMBINABLE_EXPONENT};
+use crate::config_node::{self, Node};
+
+pub const TRANSMITTER_MODULE: &str = "ModuleDataTransmitter";
+
+pub struct Import {
+    pub antennas: Vec<AntennaDef>,
+    pub warnings: Vec<String>,
+}
+
+pub fn import_gamedata(dir: &Path) -> Result<Import> {
+    let mut files = Vec::new();
+    collect_cfg_files(dir, &mut files)
+        .map_err(|e| Error::msg(format!("{}: {}", dir.display(), e)))?;
+    files.sort();
+
+    let mut warnings = Vec::new();
+    let mut parts: Vec<Node> = Vec::new();
+    let mut patches: Vec<(PathBuf, Node)> = Vec::new();
+    for path in files {
+        let root = match fs::read_to_string(&path)
+            .map_err(Error::from)
+            .and_then(|s| config_node::parse(&s))
+        {
+            Ok(root) => root,
+            Err(e) => {
+                warnings.push(format!("{}: {}, skipped", path.display(), e));
+                continue;
+            }
+        };
+
+        for node in root.nodes {
+            if node.name == "PART" {
+                parts.push(node);
+            } else if is_patch(&node.name) {
+                patches.push((path.clone(), node));
+            }
+        }
+    }
+
+    // Mods are the directories of GameData, and the names of `:FOR` passes.
+    let mut mods: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| Error::msg(format!("{}: {}", dir.display(), e)))?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_lowercase())
+        .collect();
+
+    let mut passes = Vec::new();
+    for (path, patch) in &patches {
+        let at = format!("{}:{}", path.display(), patch.line);
+        match patch_pass(&parse_header(&patch.name)) {
+            Some(pass) => {
+                if let Pass::Mod(m, 1) = &pass {
+                    mods.push(m.clone());
+                }
+                passes.push((pass, at, patch));
+            }
+            None => warnings.push(format!("{}: unsupported patch '{}'", at, patch.name)),
+        }
+    }
+
+    // The sort is stable, so patches of the same pass stay in the order of their files.
+    passes.sort_by(|a, b| a.0.cmp(&b.0));
+    for (pass, at, patch) in passes {
+        let needed = match &pass {
+            Pass::Mod(m, _) | Pass::Last(m) => Some(m),
+            _ => None,
+        };
+        if needed.map_or(false, |m| !mods.contains(m)) {
+            continue;
+        }
+        apply_part_patch(dir, &mut parts, patch, &at, &mut warnings);
+    }
+
+    let mut antennas = Vec::new();
+    for part in &parts {
+        if let Some(def) = part_antenna(part, &mut warnings) {
+            antennas.push(def);
+        }
+    }
+
+    Ok(Import { antennas, warnings })
+}
+
+/// Converts a part with a transmitter module into an antenna definition.
+/// Internal transmitters of command parts are skipped.
+pub fn part_antenna(part: &Node, warnings: &mut Vec<String>) -> Option<AntennaDef> {
+    let module = part
+        .nodes_named("MODULE")
+        .find(|m| m.value("name") == Some(TRANSMITTER_MODULE))?;
+    let part_name = part.value("name").unwrap_or_default();
+
+    let antenna_type = module.value("antennaType").unwrap_or("DIRECT");
+    if antenna_type.eq_ignore_ascii_case("INTERNAL") {
+        return None;
+    }
+
+    let power = match module.value("antennaPower").map(str::parse::<f64>) {
+        Some(Ok(p)) if p > 0.0 => p,
+        _ => {
+            warnings.push(format!(
+                "part '{}' has no valid antennaPower, skipped",
+                part_name
+            ));
+            return None;
+        }
+    };
+
+    let packet_value = |key: &str| module.value(key).and_then(|v| v.parse::<f64>().ok());
+    let (packet_size, packet_interval, packet_cost) = match (
+        packet_value("packetSize"),
+        packet_value("packetInterval"),
+        packet_value("packetResourceCost"),
+    ) {
+        (Some(s), Some(i), Some(c)) if s > 0.0 && i > 0.0 && c >= 0.0 => {
+            (Some(s), Some(i), Some(c))
+        }
+        (None, None, None) => (None, None, None),
+        _ => {
+            warnings.push(format!(
+                "part '{}' has incomplete or invalid packet values, ignored for --science",
+                part_name
+            ));
+            (None, None, None)
+        }
+    };
+
+    let (name, aliases) = match part.value("title") {
+        Some(t) if !t.is_empty() && !t.starts_with('#') => {
+            (t.to_owned(), vec![part_name.to_owned()])
+        }
+        _ => (part_name.to_owned(), Vec::new()),
+    };
+
+    Some(AntennaDef {
+        name,
+        aliases,
+        power,
+        combinable: module
+            .value("antennaCombinable")
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false),
+        combinable_exponent: module
+            .value("antennaCombinableExponent")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_COMBINABLE_EXPONENT),
+        relay: antenna_type.eq_ignore_ascii_case("RELAY"),
+        ground_station: None,
+        mass: part.value("mass").and_then(|v| v.parse().ok()),
+        cost: part.value("cost").and_then(|v| v.parse().ok()),
+        packet_size,
+        packet_interval,
+        packet_cost,
+    })
+}
+
+/// Collects `.cfg` files recursively. Symlinked directories are skipped, as they may loop.
+fn collect_cfg_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_cfg_files(&path, files)?;
+        } else if file_type.is_symlink() && path.is_dir() {
+            continue;
+        } else if path
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("cfg"))
+            .unwrap_or(false)
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_patch(name: &str) -> bool {
+    name.starts_with(|c| "@+$-!%".contains(c)) || name.contains(':') || name.contains('[')
+}
+
+/// Header of a patch node, like `@PART[name]:NEEDS[Mod]`.
+struct Header<'a> {
+    op: Option<char>,
+    node_type: &'a str,
+    filter: Option<&'a str>,
+    suffixes: Vec<(&'a str, &'a str)>,
+}
+
+fn parse_header(name: &str) -> Header<'_> {
+    let (op, rest) = match name.chars().next() {
+        Some(c) if "@+$-!%".contains(c) => (Some(c), &name[1..]),
+        _ => (None, name),
+    };
+
+    let mut segments = split_outside_brackets(rest, ':').into_iter();
+    let head = segments.next().unwrap_or_default();
+    let (node_type, filter) = split_bracket(head);
+
+    let suffixes = segments
+        .map(|s| {
+            let (k, v) = split_bracket(s);
+            (k, v.unwrap_or_default())
+        })
+        .collect();
+
+    Header {
+        op,
+        node_type,
+        filter,
+        suffixes,
+    }
+}
+
+/// ModuleManager pass of a patch. Passes run in the order of the variants,
+/// and the passes of mods in the order of their names.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Pass {
+    First,
+    /// Patches without a pass.
+    Legacy,
+    /// `:BEFORE`, `:FOR` and `:AFTER` of a mod, as 0, 1 and 2.
+    Mod(String, u8),
+    Last(String),
+    Final,
+}
+
+/// Pass of a patch, or `None` if it has more than one.
+fn patch_pass(header: &Header) -> Option<Pass> {
+    let mut passes = header.suffixes.iter().filter_map(|(k, v)| {
+        let m = v.to_lowercase();
+        match *k {
+            "FIRST" => Some(Pass::First),
+            "BEFORE" => Some(Pass::Mod(m, 0)),
+            "FOR" => Some(Pass::Mod(m, 1)),
+            "AFTER" => Some(Pass::Mod(m, 2)),
+            "LAST" => Some(Pass::Last(m)),
+            "FINAL" => Some(Pass::Final),
+            _ => None,
+        }
+    });
+
+    match (passes.next(), passes.next()) {
+        (None, _) => Some(Pass::Legacy),
+        (Some(pass), None) => Some(pass),
+        (Some(_), Some(_)) => None,
+    }
+}
+
+fn split_bracket(s: &str) -> (&str, Option<&str>) {
+    match (s.find('['), s.rfind(']')) {
+        (Some(b), Some(e)) if b < e => (&s[..b], Some(&s[b + 1..e])),
+        _ => (s, None),
+    }
+}
+
+fn split_outside_brackets(s: &str, sep: char) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ if c == sep && depth == 0 => {
+                out.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    out.push(&s[start..]);
+    out
+}
+
+fn apply_part_patch(
+    gamedata: &Path,
+    parts: &mut [Node],
+    patch: &Node,
+    at: &str,
+    warnings: &mut Vec<String>,
+) {
+    let header = parse_header(&patch.name);
+
+    if header.op != Some('@') || header.node_type != "PART" {
+        warnings.push(format!("{}: unsupported patch '{}'", at, patch.name));
+        return;
+    }
+
+    for (k, v) in &header.suffixes {
+        match *k {
+            "NEEDS" => {
+                if !needs_satisfied(gamedata, v) {
+                    return;
+                }
+            }
+            // Passes are ordered by `import_gamedata`.
+            "FOR" | "BEFORE" | "AFTER" | "FIRST" | "LAST" | "FINAL" => {}
+            _ => {
+                warnings.push(format!("{}: unsupported patch '{}'", at, patch.name));
+                return;
+            }
+        }
+    }
+
+    let filter = header.filter.unwrap_or("*");
+    for part in parts.iter_mut() {
+        if node_matches(part, filter) {
+            apply_patch(part, patch, at, warnings);
+        }
+    }
+}
+
+fn apply_patch(target: &mut Node, patch: &Node, at: &str, warnings: &mut Vec<String>) {
+    for (key, value) in &patch.values {
+        apply_value(target, key, value, at, warnings);
+    }
+
+    for child in &patch.nodes {
+        let header = parse_header(&child.name);
+        if !header.suffixes.is_empty() {
+            warnings.push(format!("{}: unsupported node patch '{}'", at, child.name));
+            continue;
+        }
+
+        match header.op {
+            None => target.nodes.push(child.clone()),
+            Some('@') => {
+                let filter = header.filter.unwrap_or("*");
+                let matched = target
+                    .nodes
+                    .iter_mut()
+                    .find(|n| n.name == header.node_type && node_matches(n, filter));
+                match matched {
+                    Some(n) => apply_patch(n, child, at, warnings),
+                    None => warnings.push(format!(
+                        "{}: no node for '{}' in part '{}'",
+                        at,
+                        child.name,
+                        target.value("name").unwrap_or_default()
+                    )),
+                }
+            }
+            Some('-') | Some('!') => {
+                let filter = header.filter.unwrap_or("*");
+                target
+                    .nodes
+                    .retain(|n| !(n.name == header.node_type && node_matches(n, filter)));
+            }
+            _ => warnings.push(format!("{}: unsupported node patch '{}'", at, child.name)),
+        }
+    }
+}
+
+fn apply_value(target: &mut Node, key: &str, value: &str, at: &str, warnings: &mut Vec<String>) {
+    let (op, key) = match key.chars().next() {
+        Some(c) if "@%-!".contains(c) => (Some(c), &key[1..]),
+        Some(c) if "+$".contains(c) => {
+            warnings.push(format!("{}: unsupported value patch '{}'", at, key));
+            return;
+        }
+        _ => (None, key),
+    };
+    let (key, math) = match key.chars().last() {
+        Some(c) if "*/+-".contains(c) => (key[..key.len() - 1].trim_end(), Some(c)),
+        _ => (key, None),
+    };
+
+    let existing = target.values.iter().position(|(k, _)| k == key);
+    match (op, existing) {
+        (None, _) | (Some('%'), None) => target.values.push((key.to_owned(), value.to_owned())),
+        (Some('-'), _) | (Some('!'), _) => target.values.retain(|(k, _)| k != key),
+        (Some(_), Some(i)) => {
+            let v = &mut target.values[i].1;
+            match math {
+                None => *v = value.to_owned(),
+                Some(m) => match (v.parse::<f64>(), value.parse::<f64>()) {
+                    (Ok(a), Ok(b)) => {
+                        let r = match m {
+                            '*' => a * b,
+                            '/' => a / b,
+                            '+' => a + b,
+                            _ => a - b,
+                        };
+                        *v = r.to_string();
+                    }
+                    _ => warnings.push(format!("{}: '{}' is not a number", at, key)),
+                },
+            }
+        }
+        (Some(_), None) => {}
+    }
+}
+
+/// Matches the name of a node against a filter like `name`, `long*` or `a|b`.
+fn node_matches(node: &Node, filter: &str) -> bool {
+    filter == "*"
+        || node
+            .value("name")
+            .map(|n| filter.split('|').any(|f| wildcard_match(f.trim(), n)))
+            .unwrap_or(false)
+}
+
+/// Evaluates `NEEDS[...]` against the mod directories in GameData.
+fn needs_satisfied(gamedata: &Path, needs: &str) -> bool {
+    needs.split(|c| c == ',' || c == '&').all(|clause| {
+        clause.split('|').any(|m| {
+            let m = m.trim();
+            match m.strip_prefix('!') {
+                Some(m) => !gamedata.join(m).is_dir(),
+                None => gamedata.join(m).is_dir(),
+            }
+        })
+    })
+}
+
+/// Matches `name` against a pattern with `*` and `?` wildcards.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+
+    let (mut pi, mut ni) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((sp, sn)) = star {
+            pi = sp + 1;
+            ni = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Directory under the temporary directory, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let path = std::env::temp_dir().join(format!(
+                "ksp-commnet-gamedata-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn write(&self, name: &str, content: &str) {
+            let path = self.0.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const ANTENNA_PART: &str = "
+PART
+{
+    name = longAntenna
+    title = Communotron 16
+    mass = 0.005
+    cost = 300
+    MODULE
+    {
+        name = ModuleDataTransmitter
+        antennaType = DIRECT
+        packetInterval = 0.6
+        packetSize = 2
+        packetResourceCost = 12.0
+        antennaPower = 500000
+        antennaCombinable = True
+        antennaCombinableExponent = 1
+    }
+}
+";
+
+    fn first_part(source: &str) -> Node {
+        config_node::parse(source).unwrap().nodes.remove(0)
+    }
+
+    #[test]
+    fn maps_part_values() {
+        let mut warnings = Vec::new();
+        let def = part_antenna(&first_part(ANTENNA_PART), &mut warnings).unwrap();
+
+        assert!(warnings.is_empty());
+        assert_eq!(def.name, "Communotron 16");
+        assert_eq!(def.aliases, vec!["longAntenna".to_owned()]);
+        assert_eq!(def.power, 500000.0);
+        assert!(def.combinable);
+        assert_eq!(def.combinable_exponent, 1.0);
+        assert!(!def.relay);
+        assert_eq!(def.mass, Some(0.005));
+        assert_eq!(def.cost, Some(300.0));
+        assert_eq!(def.packet_size, Some(2.0));
+        assert_eq!(def.packet_interval, Some(0.6));
+        assert_eq!(def.packet_cost, Some(12.0));
+    }
+
+    #[test]
+    fn maps_relay_and_defaults() {
+        let part = first_part(
+            "PART
+{
+    name = relayAntenna
+    title = #autoLOC_500000
+    MODULE
+    {
+        name = ModuleDataTransmitter
+        antennaType = RELAY
+        antennaPower = 2000000000
+        packetSize = 2
+    }
+}",
+        );
+        let mut warnings = Vec::new();
+        let def = part_antenna(&part, &mut warnings).unwrap();
+
+        assert_eq!(def.name, "relayAntenna");
+        assert!(def.aliases.is_empty());
+        assert!(def.relay);
+        assert!(!def.combinable);
+        assert_eq!(def.combinable_exponent, DEFAULT_COMBINABLE_EXPONENT);
+        assert_eq!(def.mass, None);
+        assert_eq!(def.packet_size, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("ignored for --science"));
+    }
+
+    #[test]
+    fn skips_parts_without_power() {
+        let part =
+            first_part("PART\n{\n name = p\n MODULE\n {\n  name = ModuleDataTransmitter\n }\n}");
+        let mut warnings = Vec::new();
+        assert!(part_antenna(&part, &mut warnings).is_none());
+        assert!(warnings[0].contains("no valid antennaPower"));
+
+        let part = first_part("PART\n{\n name = p\n MODULE\n {\n  name = ModuleCommand\n }\n}");
+        let mut warnings = Vec::new();
+        assert!(part_antenna(&part, &mut warnings).is_none());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn applies_patches_with_needs() {
+        let dir = TempDir::new("needs");
+        fs::create_dir_all(dir.0.join("SomeMod")).unwrap();
+        dir.write("Squad/Parts/antenna.cfg", ANTENNA_PART);
+        dir.write(
+            "SomeMod/patches.cfg",
+            "
+@PART[long*]:NEEDS[SomeMod]:FOR[SomeMod]
+{
+    @title = Long Antenna
+    @MODULE[ModuleDataTransmitter]
+    {
+        @antennaPower *= 2
+        @antennaType = RELAY
+    }
+}
+@PART[longAntenna]:NEEDS[MissingMod]
+{
+    @mass = 9
+}
+@PART[short*]
+{
+    @cost = 1
+}
+",
+        );
+
+        let import = import_gamedata(&dir.0).unwrap();
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert_eq!(import.antennas.len(), 1);
+
+        let def = &import.antennas[0];
+        assert_eq!(def.name, "Long Antenna");
+        assert_eq!(def.power, 1000000.0);
+        assert!(def.relay);
+        assert_eq!(def.mass, Some(0.005));
+        assert_eq!(def.cost, Some(300.0));
+    }
+
+    #[test]
+    fn warns_unapplied_patches() {
+        let dir = TempDir::new("warnings");
+        dir.write("antenna.cfg", ANTENNA_PART);
+        dir.write(
+            "patches.cfg",
+            "
++PART[longAntenna]
+{
+    @name = longAntenna2
+}
+@PART[longAntenna]:HAS[#mass]
+{
+    @mass = 1
+}
+@PART[longAntenna]
+{
+    @MODULE[ModuleDeployableAntenna]
+    {
+        @extendAnimationName = deploy
+    }
+}
+",
+        );
+
+        let import = import_gamedata(&dir.0).unwrap();
+        assert_eq!(import.warnings.len(), 3, "{:?}", import.warnings);
+        assert!(import.warnings[0].contains("unsupported patch '+PART[longAntenna]'"));
+        assert!(import.warnings[0].contains("patches.cfg:3"));
+        assert!(import.warnings[1].contains("unsupported patch '@PART[longAntenna]:HAS[#mass]'"));
+        assert!(import.warnings[2].contains("no node for '@MODULE[ModuleDeployableAntenna]'"));
+        assert_eq!(import.antennas[0].mass, Some(0.005));
+    }
+
+    #[test]
+    fn applies_patches_in_pass_order() {
+        let dir = TempDir::new("passes");
+        dir.write("Squad/Parts/antenna.cfg", ANTENNA_PART);
+        let patch = |pass: &str, edit: &str| {
+            format!(
+                "@PART[longAntenna]{}\n{{\n @MODULE[ModuleDataTransmitter]\n {{\n  @antennaPower {}\n }}\n}}\n",
+                pass, edit
+            )
+        };
+        // File names sort against the order of the passes.
+        dir.write("0.cfg", &patch(":FINAL", "/= 2"));
+        dir.write("1.cfg", &patch(":LAST[ZMod]", "-= 10"));
+        dir.write("2.cfg", &patch(":AFTER[ZMod]", "*= 2"));
+        dir.write("3.cfg", &patch(":BEFORE[MissingMod]", "= 1"));
+        dir.write("4.cfg", &patch(":FOR[ZMod]", "+= 5"));
+        dir.write("5.cfg", &patch("", "*= 3"));
+        dir.write("6.cfg", &patch(":FIRST", "= 10"));
+        dir.write("7.cfg", &patch(":FOR[ZMod]:FINAL", "= 1"));
+
+        let import = import_gamedata(&dir.0).unwrap();
+        assert_eq!(import.warnings.len(), 1, "{:?}", import.warnings);
+        assert!(import.warnings[0].contains("7.cfg:2: unsupported patch"));
+        // ((10 * 3 + 5) * 2 - 10) / 2
+        assert_eq!(import.antennas[0].power, 30.0);
+    }
+
+    #[test]
+    fn matches_name_lists() {
+        let dir = TempDir::new("names");
+        dir.write("antenna.cfg", ANTENNA_PART);
+        dir.write("patches.cfg", "@PART[foo|longAntenna]\n{\n @cost = 1\n}\n");
+
+        let import = import_gamedata(&dir.0).unwrap();
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert_eq!(import.antennas[0].cost, Some(1.0));
+    }
+
+    #[test]
+    fn skips_internal_transmitters() {
+        let part = first_part(
+            "PART
+{
+    name = probeCoreOcto
+    MODULE
+    {
+        name = ModuleCommand
+    }
+    MODULE
+    {
+        name = ModuleDataTransmitter
+        antennaType = INTERNAL
+        antennaPower = 5000
+    }
+}",
+        );
+        let mut warnings = Vec::new();
+        assert!(part_antenna(&part, &mut warnings).is_none());
+        assert!(warnings.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_symlinked_directories() {
+        let dir = TempDir::new("symlinks");
+        dir.write("Mod/antenna.cfg", ANTENNA_PART);
+        std::os::unix::fs::symlink(&dir.0, dir.0.join("Mod/loop")).unwrap();
+
+        let import = import_gamedata(&dir.0).unwrap();
+        assert_eq!(import.antennas.len(), 1);
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        assert!(wildcard_match("*", "longAntenna"));
+        assert!(wildcard_match("long*", "longAntenna"));
+        assert!(wildcard_match("l?ng*a", "longAntenna"));
+        assert!(wildcard_match("*Antenna", "longAntenna"));
+        assert!(!wildcard_match("short*", "longAntenna"));
+        assert!(!wildcard_match("long", "longAntenna"));
+    }
+}
@@ -0,0 +1,319 @@
+//! Min and max distances between two orbits.
+//!
+//! Each orbit is reduced to the range of its distance from the center of its
+//! body, and the ranges are combined up to the closest common ancestor of
+//! both bodies. The result is the range of distances over all possible
+//! positions, ignoring inclinations.
+
+use anyhow::{Error, Result};
+
+use ksp_commnet_calculator_core::distance::Distances;
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+
+use crate::bodies::{Body, System};
+use crate::signal::SectionStrength;
+
+/// Orbit of an endpoint around a body, or the body itself if both radii are zero.
+#[derive(Debug, Clone)]
+pub struct OrbitSpec {
+    pub body: String,
+    /// Periapsis from the center of the body, in meters.
+    pub periapsis: f64,
+    /// Apoapsis from the center of the body, in meters.
+    pub apoapsis: f64,
+}
+
+impl OrbitSpec {
+    pub fn circular(body: &Body, altitude: f64) -> OrbitSpec {
+        let r = body.radius + altitude;
+        OrbitSpec {
+            body: body.name.clone(),
+            periapsis: r,
+            apoapsis: r,
+        }
+    }
+
+    pub fn elliptic(body: &Body, sma: f64, eccentricity: f64) -> Result<OrbitSpec> {
+        if !(0.0..1.0).contains(&eccentricity) {
+            return Err(Error::msg(format!(
+                "eccentricity should be in 0 <= e < 1, but {}",
+                eccentricity
+            )));
+        }
+
+        Ok(OrbitSpec {
+            body: body.name.clone(),
+            periapsis: sma * (1.0 - eccentricity),
+            apoapsis: sma * (1.0 + eccentricity),
+        })
+    }
+
+    /// Center of the body.
+    pub fn center(body: &Body) -> OrbitSpec {
+        OrbitSpec {
+            body: body.name.clone(),
+            periapsis: 0.0,
+            apoapsis: 0.0,
+        }
+    }
+
+    /// Checks that the orbit is above the surface and inside the SOI of its body.
+    pub fn validate(&self, body: &Body) -> Result<()> {
+        if self.periapsis < body.radius {
+            return Err(Error::msg(format!(
+                "orbit around {} is below its surface",
+                body.name
+            )));
+        }
+        if self.apoapsis > body.soi {
+            return Err(Error::msg(format!(
+                "orbit around {} is out of its SOI",
+                body.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Range of distance from the center of some body.
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    min: f64,
+    max: f64,
+}
+
+impl Span {
+    /// Span from the parent of the body, when `self` is relative to the body.
+    fn lift(self, body: &Body) -> Span {
+        Span {
+            min: (body.periapsis() - self.max).max(0.0),
+            max: body.apoapsis() + self.max,
+        }
+    }
+
+    fn separation(self, other: Span) -> (f64, f64) {
+        let min = (self.min - other.max).max(other.min - self.max).max(0.0);
+        (min, self.max + other.max)
+    }
+}
+
+/// Min and max distances between two endpoints.
+pub fn separation(system: &System, a: &OrbitSpec, b: &OrbitSpec) -> Result<(f64, f64)> {
+    let body_a = find_body(system, &a.body)?;
+    let body_b = find_body(system, &b.body)?;
+
+    let path_a = system.ancestors(body_a);
+    let path_b = system.ancestors(body_b);
+    let common = path_a
+        .iter()
+        .position(|x| path_b.iter().any(|y| y.name == x.name))
+        .ok_or_else(|| {
+            Error::msg(format!(
+                "{} and {} are not in the same system",
+                a.body, b.body
+            ))
+        })?;
+    let lca = &path_a[common].name;
+
+    let span_a = lift_to(&path_a, lca, a);
+    let span_b = lift_to(&path_b, lca, b);
+
+    Ok(span_a.separation(span_b))
+}
+
+fn lift_to(path: &[&Body], lca: &str, orbit: &OrbitSpec) -> Span {
+    let mut span = Span {
+        min: orbit.periapsis,
+        max: orbit.apoapsis,
+    };
+    for body in path.iter().take_while(|b| b.name != lca) {
+        span = span.lift(body);
+    }
+    span
+}
+
+fn find_body<'a>(system: &'a System, name: &str) -> Result<&'a Body> {
+    system
+        .get(name)
+        .ok_or_else(|| Error::msg(format!("unknown body '{}'", name)))
+}
+
+/// Section of the report, with min and max distances between the centers of two bodies.
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub name: String,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Sections of the report: the stock ones, or ones generated from a custom system.
+pub enum Sections {
+    Stock(Distances),
+    Custom(Vec<Section>),
+}
+
+impl Sections {
+    pub fn stock() -> Sections {
+        Sections::Stock(Distances::new())
+    }
+
+    /// Sections like the stock ones: home to its moons and the other planets, and planets to their moons.
+    pub fn generate(system: &System) -> Result<Sections> {
+        let home = system.home();
+        let star = system
+            .iter()
+            .find(|b| b.parent.is_none())
+            .ok_or_else(|| Error::msg("no star in the system"))?;
+        let children = |parent: &Body| -> Vec<&Body> {
+            system
+                .iter()
+                .filter(|b| b.parent.as_deref() == Some(parent.name.as_str()))
+                .collect()
+        };
+
+        let mut pairs = Vec::new();
+        for moon in children(home) {
+            pairs.push((home, moon));
+        }
+        for planet in children(star) {
+            if planet.name != home.name {
+                pairs.push((home, planet));
+            }
+        }
+        for planet in children(star) {
+            if planet.name == home.name {
+                continue;
+            }
+            for moon in children(planet) {
+                pairs.push((planet, moon));
+            }
+        }
+
+        let mut sections = Vec::new();
+        for (a, b) in pairs {
+            let (min, max) = separation(system, &OrbitSpec::center(a), &OrbitSpec::center(b))?;
+            sections.push(Section {
+                name: format!("{} - {}", a.name, b.name),
+                min,
+                max,
+            });
+        }
+        Ok(Sections::Custom(sections))
+    }
+
+    pub fn strengths(&self, from: &Endpoint, to: &Endpoint) -> Vec<SectionStrength> {
+        let range = from.range_to(to);
+        match self {
+            Sections::Stock(dists) => dists
+                .get_strengthes(range)
+                .iter()
+                .map(SectionStrength::from)
+                .collect(),
+            Sections::Custom(sections) => {
+                let max_distance = range.max_distance();
+                sections
+                    .iter()
+                    .map(|s| SectionStrength::new(s.name.clone(), max_distance, s.min, s.max))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-9 + 1e-6,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn coplanar_orbits_around_one_body() {
+        let system = System::stock();
+        let kerbin = system.get("Kerbin").unwrap();
+        let low = OrbitSpec::circular(kerbin, 100e3);
+        let high = OrbitSpec::circular(kerbin, 2868e3);
+
+        let (min, max) = separation(&system, &low, &high).unwrap();
+        assert_close(min, high.periapsis - low.apoapsis);
+        assert_close(max, high.apoapsis + low.apoapsis);
+
+        // The order of the endpoints does not matter.
+        let (min2, max2) = separation(&system, &high, &low).unwrap();
+        assert_close(min2, min);
+        assert_close(max2, max);
+    }
+
+    #[test]
+    fn elliptic_orbits_overlap() {
+        let system = System::stock();
+        let kerbin = system.get("Kerbin").unwrap();
+        let a = OrbitSpec::elliptic(kerbin, 2e6, 0.5).unwrap();
+        let b = OrbitSpec::circular(kerbin, 1e6);
+
+        let (min, max) = separation(&system, &a, &b).unwrap();
+        assert_close(min, 0.0);
+        assert_close(max, a.apoapsis + b.apoapsis);
+    }
+
+    #[test]
+    fn parent_and_child() {
+        let system = System::stock();
+        let kerbin = system.get("Kerbin").unwrap();
+        let mun = system.get("Mun").unwrap();
+        let orbit = OrbitSpec::circular(kerbin, 100e3);
+
+        let (min, max) = separation(&system, &orbit, &OrbitSpec::center(mun)).unwrap();
+        assert_close(min, mun.periapsis() - orbit.apoapsis);
+        assert_close(max, mun.apoapsis() + orbit.apoapsis);
+    }
+
+    #[test]
+    fn siblings() {
+        let system = System::stock();
+        let mun = system.get("Mun").unwrap();
+        let minmus = system.get("Minmus").unwrap();
+
+        let (min, max) =
+            separation(&system, &OrbitSpec::center(mun), &OrbitSpec::center(minmus)).unwrap();
+        assert_close(min, minmus.periapsis() - mun.apoapsis());
+        assert_close(max, minmus.apoapsis() + mun.apoapsis());
+    }
+
+    #[test]
+    fn rejects_unknown_body_and_bad_orbits() {
+        let system = System::stock();
+        let kerbin = system.get("Kerbin").unwrap();
+
+        let orbit = OrbitSpec::circular(kerbin, 100e3);
+        let mut unknown = orbit.clone();
+        unknown.body = "Nowhere".to_owned();
+        assert!(separation(&system, &orbit, &unknown).is_err());
+
+        assert!(OrbitSpec::elliptic(kerbin, 1e6, 1.0).is_err());
+        assert!(OrbitSpec::circular(kerbin, -1e3).validate(kerbin).is_err());
+        assert!(OrbitSpec::circular(kerbin, 1e12).validate(kerbin).is_err());
+    }
+
+    #[test]
+    fn generated_sections_match_system() {
+        let system = System::stock();
+        let sections = match Sections::generate(&system).unwrap() {
+            Sections::Custom(s) => s,
+            Sections::Stock(_) => panic!("expected custom sections"),
+        };
+
+        let mun = sections.iter().find(|s| s.name == "Kerbin - Mun").unwrap();
+        let body = system.get("Mun").unwrap();
+        assert_close(mun.min, body.periapsis());
+        assert_close(mun.max, body.apoapsis());
+        assert!(sections.iter().any(|s| s.name == "Kerbin - Duna"));
+        assert!(sections.iter().any(|s| s.name == "Duna - Ike"));
+    }
+}
@@ -0,0 +1,57 @@
+//! Signal strengths of KSP CommNet links, as used by the `ksp-commnet-calculator-cli` command.
+//!
+//! Build endpoints with [`endpoint::EndpointBuilder`] or directly from antenna
+//! specifiers with [`report::ReportBuilder`], and render the [`report::Report`]
+//! with one of the [`render`] modules.
+//!
+//! With the default `cli` feature, the command itself is [`cli::run`], and each
+//! subcommand has its own module like [`solve`], which runs with the matches of
+//! [`cli::app`]. Without it, the crate is only the library, without the
+//! dependencies of the command.
+
+#[cfg(feature = "cli")]
+pub mod audit;
+pub mod bodies;
+pub mod calendar;
+pub mod catalog;
+#[cfg(feature = "cli")]
+pub mod chain;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod config_node;
+#[cfg(feature = "cli")]
+pub mod constellation;
+#[cfg(feature = "cli")]
+pub mod coverage;
+pub mod craft;
+pub mod endpoint;
+pub mod ephemeris;
+pub mod gamedata;
+pub mod geometry;
+pub mod metric;
+pub mod occlusion;
+pub mod parts;
+pub mod render;
+#[cfg(feature = "cli")]
+pub mod repl;
+pub mod report;
+pub mod save;
+pub mod scenario;
+pub mod science;
+#[cfg(feature = "cli")]
+pub mod serve;
+pub mod signal;
+#[cfg(feature = "cli")]
+pub mod solve;
+pub mod suggest;
+#[cfg(feature = "cli")]
+pub mod timeline;
+#[cfg(feature = "cli")]
+pub mod workbench;
+
+pub(crate) const INDENT: &str = "    ";
+
+/// Antenna of `from` if none is given.
+pub const DEFAULT_FROM: &str = "DSN Lv.3";
+/// Antenna of `to` if none is given.
+pub const DEFAULT_TO: &str = "Command Module";
@@ -0,0 +1,77 @@
+use anyhow::{Error, Result};
+
+const PREFIXES: &[(char, f64)] = &[
+    ('k', 1e3),
+    ('M', 1e6),
+    ('G', 1e9),
+    ('T', 1e12),
+    ('P', 1e15),
+    ('E', 1e18),
+];
+
+/// Parses a distance printed with `MetricPrefix`, such as `12.5Gm`, `84M` or `3.4e9`.
+///
+/// A trailing `m` is the unit (meters), not the milli prefix.
+pub fn parse_distance(s: &str) -> Result<f64> {
+    let trimmed = s.trim();
+    let without_unit = trimmed.strip_suffix('m').unwrap_or(trimmed).trim_end();
+
+    let (number, factor) = match without_unit.chars().last() {
+        Some(c) => match PREFIXES.iter().find(|(p, _)| *p == c) {
+            Some((_, f)) => (&without_unit[..without_unit.len() - c.len_utf8()], *f),
+            None => (without_unit, 1.0),
+        },
+        None => (without_unit, 1.0),
+    };
+
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| Error::msg(format!("invalid distance: {}", s)))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(Error::msg(format!("invalid distance: {}", s)));
+    }
+
+    Ok(value * factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ksp_commnet_calculator_core::util::MetricPrefix;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn parses_prefixes_and_unit() {
+        assert_close(parse_distance("12.5Gm").unwrap(), 12.5e9);
+        assert_close(parse_distance("84M").unwrap(), 84e6);
+        assert_close(parse_distance(" 600 km ").unwrap(), 600e3);
+        assert_close(parse_distance("3.4e9").unwrap(), 3.4e9);
+        assert_close(parse_distance("250m").unwrap(), 250.0);
+        assert_close(parse_distance("0").unwrap(), 0.0);
+    }
+
+    #[test]
+    fn rejects_invalid_distances() {
+        for s in &["", "m", "abc", "12Xm", "-5Mm", "inf", "NaN", "1.2.3k"] {
+            assert!(parse_distance(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn round_trips_metric_prefix() {
+        for &d in &[500.0, 2.5e3, 600e3, 84e6, 12.5e9, 3.4e12, 5e15, 2e18] {
+            let printed = format!("{}m", MetricPrefix(d));
+            assert_close(parse_distance(&printed).unwrap(), d);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Line of sight around a body: relays of a constellation and points on the
+//! surface.
+//!
+//! Positions are in a frame centered on the body, with the equator on the
+//! x-y plane. The body is a sphere of its radius, times the occlusion
+//! multiplier.
+
+use std::f64::consts::PI;
+
+use crate::bodies::Body;
+
+pub type Vec3 = [f64; 3];
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn distance(a: Vec3, b: Vec3) -> f64 {
+    let d = sub(a, b);
+    dot(d, d).sqrt()
+}
+
+/// Whether the segment between `a` and `b` clears a sphere of `radius` at the center.
+pub fn line_of_sight(a: Vec3, b: Vec3, radius: f64) -> bool {
+    let ab = sub(b, a);
+    let len2 = dot(ab, ab);
+    if len2 == 0.0 {
+        return dot(a, a) > radius * radius;
+    }
+
+    // Closest point of the segment to the center.
+    let t = (-dot(a, ab) / len2).clamp(0.0, 1.0);
+    let p = [a[0] + ab[0] * t, a[1] + ab[1] * t, a[2] + ab[2] * t];
+    dot(p, p) > radius * radius
+}
+
+/// Whether `target` is above the horizon of the surface point `point`.
+pub fn above_horizon(point: Vec3, target: Vec3) -> bool {
+    dot(sub(target, point), point) > 0.0
+}
+
+/// Whether `target` is visible from the surface point `point` past a sphere of `radius`.
+///
+/// A sphere smaller than the body lowers the horizon of the point, like the
+/// occlusion settings of the game. The point is on the surface, so a larger
+/// sphere does not raise the horizon.
+pub fn surface_line_of_sight(point: Vec3, target: Vec3, radius: f64) -> bool {
+    if radius * radius < dot(point, point) {
+        line_of_sight(point, target, radius)
+    } else {
+        above_horizon(point, target)
+    }
+}
+
+/// Relays evenly phased on a circular orbit.
+#[derive(Debug, Clone)]
+pub struct Constellation {
+    pub count: usize,
+    /// Orbit radius from the center of the body in meters.
+    pub radius: f64,
+    /// Inclination in radians.
+    pub inclination: f64,
+    /// Phase between neighbouring relays in radians.
+    pub spacing: f64,
+    /// Mean motion in radians per second.
+    pub mean_motion: f64,
+}
+
+impl Constellation {
+    pub fn new(body: &Body, count: usize, altitude: f64, inclination: f64, spacing: f64) -> Self {
+        let radius = body.radius + altitude;
+        Constellation {
+            count,
+            radius,
+            inclination,
+            spacing,
+            mean_motion: (body.mu / radius.powi(3)).sqrt(),
+        }
+    }
+
+    /// Orbital period in seconds.
+    pub fn period(&self) -> f64 {
+        2.0 * PI / self.mean_motion
+    }
+
+    /// Position of the `i`-th relay at `t`.
+    pub fn position(&self, i: usize, t: f64) -> Vec3 {
+        let u = i as f64 * self.spacing + self.mean_motion * t;
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+        [
+            self.radius * u.cos(),
+            self.radius * u.sin() * cos_i,
+            self.radius * u.sin() * sin_i,
+        ]
+    }
+
+    /// Pairs of neighbouring relays, as indices.
+    pub fn neighbours(&self) -> Vec<(usize, usize)> {
+        match self.count {
+            0 | 1 => Vec::new(),
+            2 => vec![(0, 1)],
+            n => (0..n).map(|i| (i, (i + 1) % n)).collect(),
+        }
+    }
+}
+
+/// Point on the surface of a rotating body.
+#[derive(Debug, Clone, Copy)]
+pub struct SurfacePoint {
+    /// Latitude in radians.
+    pub latitude: f64,
+    /// Longitude at `t = 0` in radians.
+    pub longitude: f64,
+    pub radius: f64,
+    /// Rotation in radians per second.
+    pub rotation: f64,
+}
+
+impl SurfacePoint {
+    pub fn new(body: &Body, latitude: f64, longitude: f64) -> Self {
+        SurfacePoint {
+            latitude,
+            longitude,
+            radius: body.radius,
+            rotation: if body.rotation_period > 0.0 {
+                2.0 * PI / body.rotation_period
+            } else {
+                0.0
+            },
+        }
+    }
+
+    pub fn position(&self, t: f64) -> Vec3 {
+        let lon = self.longitude + self.rotation * t;
+        let (sin_lat, cos_lat) = self.latitude.sin_cos();
+        [
+            self.radius * cos_lat * lon.cos(),
+            self.radius * cos_lat * lon.sin(),
+            self.radius * sin_lat,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn occlusion_lowers_surface_horizon() {
+        let point = [1.0, 0.0, 0.0];
+        // Slightly below the horizon of the point.
+        let target = [0.9, 2.0, 0.0];
+
+        assert!(!above_horizon(point, target));
+        assert!(!surface_line_of_sight(point, target, 1.0));
+        assert!(!surface_line_of_sight(point, target, 1.5));
+        assert!(surface_line_of_sight(point, target, 0.9));
+        assert!(surface_line_of_sight(point, [2.0, 0.0, 0.0], 1.5));
+        assert!(!surface_line_of_sight(point, [-2.0, 0.0, 0.0], 0.9));
+    }
+}
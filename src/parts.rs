@@ -0,0 +1,205 @@
+//! Mapping from KSP part names to antennas of the catalog.
+
+use ksp_commnet_calculator_core::antenna::Antenna;
+
+use crate::catalog::Catalog;
+use crate::config_node::Node;
+use crate::gamedata::TRANSMITTER_MODULE;
+
+/// Stock antenna parts and the catalog names of their antennas.
+const STOCK_PARTS: &[(&str, &str)] = &[
+    ("longAntenna", "Communotron 16"),
+    ("SurfAntenna", "Communotron 16-S"),
+    ("mediumDishAntenna", "Communotron DTS-M1"),
+    ("HighGainAntenna", "Communotron HG-55"),
+    ("commDish", "Communotron 88-88"),
+    ("HighGainAntenna5", "HG-5"),
+    ("HighGainAntenna5_v2", "HG-5"),
+    ("RelayAntenna5", "RA-2"),
+    ("RelayAntenna50", "RA-15"),
+    ("RelayAntenna100", "RA-100"),
+];
+
+/// Antenna of command pods and probe cores.
+const INTERNAL_ANTENNA: &str = "Command Module";
+
+/// Finds the antenna of a part. Part names in saves and crafts use `.` where configs use `_`.
+pub fn part_antenna<'a>(catalog: &'a Catalog, part_name: &str) -> Option<&'a Antenna> {
+    let name = part_name.replace('.', "_");
+
+    if let Some((_, antenna)) = STOCK_PARTS.iter().find(|(p, _)| *p == name) {
+        return catalog.get(antenna);
+    }
+
+    catalog.get(&name)
+}
+
+/// Antenna parts of a vessel, by antenna name.
+#[derive(Debug, Default)]
+pub struct PartAntennas {
+    pub counts: Vec<(String, usize)>,
+    /// Recognized parts as (part name, antenna name).
+    pub recognized: Vec<(String, String)>,
+    /// Parts with antenna-like modules which are not in the catalog, as (part name, module name).
+    pub unknown: Vec<(String, String)>,
+}
+
+impl PartAntennas {
+    /// Antenna specifiers as `<NUMBER_OF_ANTENNA>:<ANTENNA_NAME>`, which `split_antenna_arg`
+    /// reads unambiguously even if the name of a modded antenna is a number.
+    pub fn specs(&self) -> Vec<String> {
+        self.counts
+            .iter()
+            .map(|(n, c)| format!("{}:{}", c, n))
+            .collect()
+    }
+
+    fn add(&mut self, part_name: &str, name: &str) {
+        self.recognized
+            .push((part_name.to_owned(), name.to_owned()));
+        match self.counts.iter_mut().find(|(n, _)| n == name) {
+            Some((_, c)) => *c += 1,
+            None => self.counts.push((name.to_owned(), 1)),
+        }
+    }
+}
+
+/// Collects antennas from `PART` nodes of a vessel or craft.
+pub fn collect_antennas<'a>(
+    catalog: &Catalog,
+    parts: impl Iterator<Item = &'a Node>,
+) -> PartAntennas {
+    let mut result = PartAntennas::default();
+
+    for part in parts {
+        // Saves have `name`, crafts have `part` with an instance ID.
+        let part_name = match (part.value("name"), part.value("part")) {
+            (Some(n), _) => n,
+            (None, Some(p)) => strip_instance_id(p),
+            (None, None) => continue,
+        };
+
+        if let Some(a) = part_antenna(catalog, part_name) {
+            result.add(part_name, &a.name);
+            continue;
+        }
+
+        let modules: Vec<&str> = part
+            .nodes_named("MODULE")
+            .filter_map(|m| m.value("name"))
+            .collect();
+        if !modules.contains(&TRANSMITTER_MODULE) {
+            for m in modules.iter().filter(|m| is_antenna_like(m)) {
+                result.unknown.push((part_name.to_owned(), (*m).to_owned()));
+            }
+            continue;
+        }
+
+        if modules.contains(&"ModuleCommand") {
+            if let Some(a) = catalog.get(INTERNAL_ANTENNA) {
+                result.add(part_name, &a.name);
+                continue;
+            }
+        }
+
+        result
+            .unknown
+            .push((part_name.to_owned(), TRANSMITTER_MODULE.to_owned()));
+    }
+
+    result
+}
+
+/// Transmitter modules of antenna mods, such as RemoteTech's `ModuleRTAntenna`.
+/// Deployment modules like `ModuleDeployableAntenna` are not antennas by themselves.
+const MOD_ANTENNA_MODULES: &[&str] = &[
+    "ModuleRTAntenna",
+    "ModuleRTAntennaPassive",
+    "ModuleRTDataTransmitter",
+    "ModuleLimitedDataTransmitter",
+];
+
+fn is_antenna_like(module: &str) -> bool {
+    MOD_ANTENNA_MODULES.contains(&module)
+}
+
+/// Strips the instance ID of craft part names, like `longAntenna_4294523412`.
+fn strip_instance_id(s: &str) -> &str {
+    match s.rfind('_') {
+        Some(p) if p + 1 < s.len() && s[p + 1..].chars().all(|c| c.is_ascii_digit()) => &s[..p],
+        _ => s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config_node;
+
+    fn collect(source: &str) -> PartAntennas {
+        let root = config_node::parse(source).unwrap();
+        collect_antennas(&Catalog::new(), root.nodes_named("PART"))
+    }
+
+    #[test]
+    fn recognizes_stock_parts() {
+        let result = collect(
+            "
+PART { name = longAntenna }
+PART { part = HighGainAntenna5.v2_4294523412 }
+PART { name = longAntenna }
+PART
+{
+    name = probeCoreOcto
+    MODULE { name = ModuleCommand }
+    MODULE { name = ModuleDataTransmitter }
+}
+",
+        );
+
+        assert_eq!(
+            result.counts,
+            vec![
+                ("Communotron 16".to_owned(), 2),
+                ("HG-5".to_owned(), 1),
+                ("Command Module".to_owned(), 1),
+            ]
+        );
+        assert!(result.unknown.is_empty());
+    }
+
+    #[test]
+    fn reports_only_transmitter_modules() {
+        let result = collect(
+            "
+PART
+{
+    name = modDish
+    MODULE { name = ModuleDeployableAntenna }
+    MODULE { name = ModuleDataTransmitter }
+}
+PART
+{
+    name = rtDish
+    MODULE { name = ModuleRTAntenna }
+    MODULE { name = ModuleDeployableAntenna }
+}
+PART
+{
+    name = solarPanel
+    MODULE { name = ModuleDeployableSolarPanel }
+}
+",
+        );
+
+        assert!(result.counts.is_empty());
+        assert_eq!(
+            result.unknown,
+            vec![
+                ("modDish".to_owned(), TRANSMITTER_MODULE.to_owned()),
+                ("rtDish".to_owned(), "ModuleRTAntenna".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,14 @@
+//! Renderers of a [`Report`](crate::report::Report) into text.
+
+pub mod delimited;
+pub mod json;
+pub mod markdown;
+
+/// Formats a strength as a percentage, or `NA` if out of range.
+pub fn format_strength(strength: Option<f64>) -> String {
+    if let Some(s) = strength {
+        format!("{:.1} %", 100.0 * s)
+    } else {
+        "NA".to_owned()
+    }
+}
@@ -0,0 +1,204 @@
+//! Comma or tab separated values.
+
+use crate::report::{Design, Report};
+use crate::science::Science;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    fn as_char(self) -> char {
+        match self {
+            Delimiter::Comma => ',',
+            Delimiter::Tab => '\t',
+        }
+    }
+}
+
+/// Section strengths as raw values.
+///
+/// With a distance, the columns `distance` and `at_distance` follow `section`
+/// and `at_max`, and the last row has an empty `section`, the distance in
+/// meters and the strength at it. The other rows leave them empty.
+///
+/// With science, the columns `time_at_min` and `time_at_max` have the transmission
+/// times in seconds, and `charge` has the electric charge. The times at the
+/// distance are empty.
+pub fn render(report: &Report, delimiter: Delimiter, header: bool) -> String {
+    let mut out = String::new();
+    let mut push = |fields: Vec<String>| {
+        let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+        out.push_str(&record(&fields, delimiter));
+        out.push('\n');
+    };
+    let science = report.science.as_ref();
+    let at = report.at_distance.as_ref();
+
+    if header {
+        let mut fields = vec!["section".to_owned()];
+        if at.is_some() {
+            fields.push("distance".to_owned());
+        }
+        fields.push("at_min".to_owned());
+        fields.push("at_max".to_owned());
+        if at.is_some() {
+            fields.push("at_distance".to_owned());
+        }
+        if science.is_some() {
+            fields.extend(SCIENCE_COLUMNS.iter().map(|c| (*c).to_owned()));
+        }
+        push(fields);
+    }
+
+    for (i, strength) in report.sections.iter().enumerate() {
+        let mut fields = vec![strength.section.clone()];
+        if at.is_some() {
+            fields.push(String::new());
+        }
+        fields.push(format_raw(strength.at_min));
+        fields.push(format_raw(strength.at_max));
+        if at.is_some() {
+            fields.push(String::new());
+        }
+        if let Some(s) = science {
+            fields.extend(science_fields(s, Some(i)));
+        }
+        push(fields);
+    }
+
+    if let Some(at) = at {
+        let mut fields = vec![
+            String::new(),
+            at.distance.to_string(),
+            String::new(),
+            String::new(),
+            format_raw(at.strength),
+        ];
+        if let Some(s) = science {
+            fields.extend(science_fields(s, None));
+        }
+        push(fields);
+    }
+
+    out
+}
+
+/// Section strengths of each design in columns `NAME_at_min` and `NAME_at_max`,
+/// followed by `NAME_at_distance` with a distance, and `NAME_time_at_min`,
+/// `NAME_time_at_max` and `NAME_charge` with science. The `distance` column and
+/// the row of the distance are like in `render`.
+///
+/// All designs should have the same sections, distance and science amount.
+pub fn render_comparison(designs: &[Design], delimiter: Delimiter, header: bool) -> String {
+    let mut out = String::new();
+    let first = match designs.first() {
+        Some(d) => &d.report,
+        None => return out,
+    };
+    let mut push = |fields: Vec<String>| {
+        let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+        out.push_str(&record(&fields, delimiter));
+        out.push('\n');
+    };
+    let at = first.at_distance.as_ref();
+
+    if header {
+        let mut fields = vec!["section".to_owned()];
+        if at.is_some() {
+            fields.push("distance".to_owned());
+        }
+        for d in designs {
+            fields.push(format!("{}_at_min", d.name));
+            fields.push(format!("{}_at_max", d.name));
+            if at.is_some() {
+                fields.push(format!("{}_at_distance", d.name));
+            }
+            if d.report.science.is_some() {
+                for c in SCIENCE_COLUMNS {
+                    fields.push(format!("{}_{}", d.name, c));
+                }
+            }
+        }
+        push(fields);
+    }
+
+    for (i, strength) in first.sections.iter().enumerate() {
+        let mut fields = vec![strength.section.clone()];
+        if at.is_some() {
+            fields.push(String::new());
+        }
+        for d in designs {
+            let s = &d.report.sections[i];
+            fields.push(format_raw(s.at_min));
+            fields.push(format_raw(s.at_max));
+            if at.is_some() {
+                fields.push(String::new());
+            }
+            if let Some(s) = &d.report.science {
+                fields.extend(science_fields(s, Some(i)));
+            }
+        }
+        push(fields);
+    }
+
+    if let Some(at) = at {
+        let mut fields = vec![String::new(), at.distance.to_string()];
+        for d in designs {
+            fields.push(String::new());
+            fields.push(String::new());
+            fields.push(format_raw(d.report.at_distance.and_then(|a| a.strength)));
+            if let Some(s) = &d.report.science {
+                fields.extend(science_fields(s, None));
+            }
+        }
+        push(fields);
+    }
+
+    out
+}
+
+const SCIENCE_COLUMNS: &[&str] = &["time_at_min", "time_at_max", "charge"];
+
+/// Transmission times of the `section`-th section, empty if `None`, and the charge.
+fn science_fields(science: &Science, section: Option<usize>) -> Vec<String> {
+    let (at_min, at_max) = match section.map(|i| &science.sections[i]) {
+        Some(s) => (format_raw(s.at_min), format_raw(s.at_max)),
+        None => (String::new(), String::new()),
+    };
+    vec![at_min, at_max, science.charge.to_string()]
+}
+
+/// Fields joined by the delimiter, quoted if needed, without a line break.
+pub fn record(fields: &[&str], delimiter: Delimiter) -> String {
+    let d = delimiter.as_char();
+
+    let mut line = String::new();
+    for (i, f) in fields.iter().enumerate() {
+        if i > 0 {
+            line.push(d);
+        }
+        write_field(&mut line, f, d);
+    }
+    line
+}
+
+fn write_field(line: &mut String, field: &str, delimiter: char) {
+    let needs_quote = field
+        .chars()
+        .any(|c| c == delimiter || c == '"' || c == '\n' || c == '\r');
+
+    if needs_quote {
+        line.push('"');
+        line.push_str(&field.replace('"', "\"\""));
+        line.push('"');
+    } else {
+        line.push_str(field);
+    }
+}
+
+fn format_raw(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
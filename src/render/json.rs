@@ -0,0 +1,213 @@
+//! JSON output of the distance report.
+//!
+//! The schema is versioned by `version`. Fields may be added within the same
+//! version, but existing fields are never renamed, removed or retyped.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "from": {
+//!     "type": "DSN",
+//!     "power": 250000000000.0,
+//!     "antennas": [{ "name": "DSN Lv.3", "count": 1 }]
+//!   },
+//!   "to": { ... },
+//!   "max_distance": 35355339.05,
+//!   "at_distance": { "distance": 12000000.0, "strength": 0.42 },
+//!   "sections": [
+//!     { "section": "Kerbin - Mun", "at_min": 0.95, "at_max": null }
+//!   ]
+//! }
+//! ```
+//!
+//! `power`, `max_distance` and `distance` are in meters, `strength`, `at_min`
+//! and `at_max` are strengths in `0.0..=1.0`, or `null` if out of range.
+//! `at_distance` is present only if `--distance` is given.
+//!
+//! With `--science`, `science` has the transmission by the vessel:
+//!
+//! ```json
+//! "science": {
+//!   "amount": 100.0,
+//!   "antenna": "Communotron 88-88",
+//!   "charge": 500.0,
+//!   "sections": [
+//!     { "section": "Kerbin - Mun", "at_min": 5.2, "at_max": null }
+//!   ]
+//! }
+//! ```
+//!
+//! `amount` is in Mits, `charge` in EC, and `at_min` and `at_max` are
+//! transmission times in seconds, or `null` if out of range.
+//!
+//! With `--design`, the report of each design has its `name`:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "designs": [
+//!     { "name": "probeA", "from": { ... }, "to": { ... }, "sections": [ ... ] }
+//!   ]
+//! }
+//! ```
+
+use anyhow::Result;
+use serde::Serialize;
+
+use ksp_commnet_calculator_core::antenna::Antenna;
+
+use crate::report::{Design, EndpointSummary, Report};
+use crate::science::Science;
+use crate::signal::SectionStrength;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct JsonReport {
+    pub version: u32,
+    pub from: JsonEndpoint,
+    pub to: JsonEndpoint,
+    pub max_distance: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at_distance: Option<JsonAtDistance>,
+    pub sections: Vec<JsonSection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub science: Option<Science>,
+}
+
+impl JsonReport {
+    pub fn new(report: &Report) -> Self {
+        JsonReport {
+            version: SCHEMA_VERSION,
+            from: JsonEndpoint::new(&report.from),
+            to: JsonEndpoint::new(&report.to),
+            max_distance: report.max_distance,
+            at_distance: report.at_distance.map(|at| JsonAtDistance {
+                distance: at.distance,
+                strength: at.strength,
+            }),
+            sections: report.sections.iter().map(JsonSection::new).collect(),
+            science: report.science.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonEndpoint {
+    #[serde(rename = "type")]
+    pub endpoint_type: String,
+    pub power: f64,
+    pub antennas: Vec<JsonAntennaCount>,
+}
+
+impl JsonEndpoint {
+    fn new(endpoint: &EndpointSummary) -> Self {
+        JsonEndpoint {
+            endpoint_type: endpoint.endpoint_type.clone(),
+            power: endpoint.power,
+            antennas: endpoint
+                .antennas
+                .iter()
+                .map(|(name, count)| JsonAntennaCount {
+                    name: name.clone(),
+                    count: *count,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonAntennaCount {
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonAtDistance {
+    pub distance: f64,
+    pub strength: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonSection {
+    pub section: String,
+    pub at_min: Option<f64>,
+    pub at_max: Option<f64>,
+}
+
+impl JsonSection {
+    fn new(strength: &SectionStrength) -> Self {
+        JsonSection {
+            section: strength.section.clone(),
+            at_min: strength.at_min,
+            at_max: strength.at_max,
+        }
+    }
+}
+
+/// Antenna in the list of `serve`.
+#[derive(Debug, Serialize)]
+pub struct JsonAntenna {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub power: f64,
+    pub relay: bool,
+}
+
+impl JsonAntenna {
+    pub fn new(antenna: &Antenna) -> Self {
+        JsonAntenna {
+            name: antenna.name.clone(),
+            aliases: antenna.aliases.clone(),
+            power: antenna.power,
+            relay: antenna.is_relay,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonComparison {
+    pub version: u32,
+    pub designs: Vec<JsonDesign>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonDesign {
+    pub name: String,
+    pub from: JsonEndpoint,
+    pub to: JsonEndpoint,
+    pub max_distance: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at_distance: Option<JsonAtDistance>,
+    pub sections: Vec<JsonSection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub science: Option<Science>,
+}
+
+impl JsonDesign {
+    pub fn new(design: &Design) -> Self {
+        let r = JsonReport::new(&design.report);
+        JsonDesign {
+            name: design.name.clone(),
+            from: r.from,
+            to: r.to,
+            max_distance: r.max_distance,
+            at_distance: r.at_distance,
+            sections: r.sections,
+            science: r.science,
+        }
+    }
+}
+
+pub fn render_comparison(designs: &[Design]) -> Result<String> {
+    let comparison = JsonComparison {
+        version: SCHEMA_VERSION,
+        designs: designs.iter().map(JsonDesign::new).collect(),
+    };
+    Ok(serde_json::to_string_pretty(&comparison)?)
+}
+
+pub fn render(report: &Report) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&JsonReport::new(report))?)
+}
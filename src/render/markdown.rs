@@ -0,0 +1,206 @@
+//! Human-readable report with markdown tables.
+
+use std::fmt::{self, Write};
+
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::calendar::format_duration;
+use crate::endpoint::Modifiers;
+use crate::render::format_strength;
+use crate::report::{Design, EndpointSummary, Report};
+use crate::science::Science;
+use crate::INDENT;
+
+pub fn render(report: &Report) -> String {
+    let mut out = String::new();
+    write_report(&mut out, report).expect("writing to a String never fails");
+    out
+}
+
+/// Reports of `from` to each design, with a column group per design.
+///
+/// All designs should have the same `from`, sections and distance.
+pub fn render_comparison(designs: &[Design]) -> String {
+    let mut out = String::new();
+    write_comparison(&mut out, designs).expect("writing to a String never fails");
+    out
+}
+
+/// Endpoint with its power and antennas, and the modifiers if not the default.
+pub fn endpoint(endpoint: &EndpointSummary, modifiers: &Modifiers) -> String {
+    let mut out = String::new();
+    write_endpoint(&mut out, endpoint, modifiers).expect("writing to a String never fails");
+    out
+}
+
+fn write_report(out: &mut String, report: &Report) -> fmt::Result {
+    writeln!(out)?;
+    writeln!(out, " From:")?;
+    write_endpoint(out, &report.from, &report.modifiers)?;
+    writeln!(out, " To:")?;
+    write_endpoint(out, &report.to, &report.modifiers)?;
+    writeln!(out)?;
+
+    writeln!(out, " Max distance: {}m", MetricPrefix(report.max_distance))?;
+    if let Some(at) = &report.at_distance {
+        writeln!(
+            out,
+            " Strength at {}m: {}",
+            MetricPrefix(at.distance),
+            format_strength(at.strength)
+        )?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, " |          Section          |   @Min   |   @Max   |")?;
+    writeln!(out, " |:--------------------------|---------:|---------:|")?;
+    for strength in &report.sections {
+        writeln!(
+            out,
+            " | {:<25} | {:>8} | {:>8} |",
+            strength.section,
+            format_strength(strength.at_min),
+            format_strength(strength.at_max),
+        )?;
+    }
+    writeln!(out)?;
+
+    if let Some(s) = &report.science {
+        write_science(out, s)?;
+    }
+    Ok(())
+}
+
+fn write_comparison(out: &mut String, designs: &[Design]) -> fmt::Result {
+    let first = match designs.first() {
+        Some(d) => &d.report,
+        None => return Ok(()),
+    };
+
+    writeln!(out)?;
+    writeln!(out, " From:")?;
+    write_endpoint(out, &first.from, &first.modifiers)?;
+    for d in designs {
+        writeln!(out, " To ({}):", d.name)?;
+        write_endpoint(out, &d.report.to, &d.report.modifiers)?;
+    }
+    writeln!(out)?;
+
+    for d in designs {
+        write!(
+            out,
+            " {}: max distance {}m",
+            d.name,
+            MetricPrefix(d.report.max_distance)
+        )?;
+        if let Some(at) = &d.report.at_distance {
+            write!(
+                out,
+                ", strength at {}m: {}",
+                MetricPrefix(at.distance),
+                format_strength(at.strength)
+            )?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)?;
+
+    // Fit each column to the design name.
+    let widths: Vec<usize> = designs
+        .iter()
+        .map(|d| (d.name.chars().count() + 5).max(8))
+        .collect();
+
+    write!(out, " |          Section          |")?;
+    for (d, w) in designs.iter().zip(&widths) {
+        write!(
+            out,
+            " {:>w$} | {:>w$} |",
+            format!("{} @Min", d.name),
+            format!("{} @Max", d.name),
+            w = *w
+        )?;
+    }
+    writeln!(out)?;
+    write!(out, " |:--------------------------|")?;
+    for w in &widths {
+        let dashes = "-".repeat(w + 1);
+        write!(out, "{}:|{}:|", dashes, dashes)?;
+    }
+    writeln!(out)?;
+
+    for (i, strength) in first.sections.iter().enumerate() {
+        write!(out, " | {:<25} |", strength.section)?;
+        for (d, w) in designs.iter().zip(&widths) {
+            let s = &d.report.sections[i];
+            write!(
+                out,
+                " {:>w$} | {:>w$} |",
+                format_strength(s.at_min),
+                format_strength(s.at_max),
+                w = *w
+            )?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)?;
+
+    for d in designs {
+        if let Some(s) = &d.report.science {
+            writeln!(out, " {}:", d.name)?;
+            write_science(out, s)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_endpoint(
+    out: &mut String,
+    endpoint: &EndpointSummary,
+    modifiers: &Modifiers,
+) -> fmt::Result {
+    writeln!(out, " {}:", endpoint.endpoint_type)?;
+
+    if !modifiers.is_default() {
+        if endpoint.ground_station {
+            writeln!(out, " {}DSN modifier: {}", INDENT, modifiers.dsn)?;
+        } else {
+            writeln!(out, " {}Range modifier: {}", INDENT, modifiers.range)?;
+        }
+    }
+
+    writeln!(out, " {}Power: {}", INDENT, MetricPrefix(endpoint.power))?;
+
+    writeln!(out, " {}Antennae:", INDENT)?;
+    for (name, c) in &endpoint.antennas {
+        if *c == 1 {
+            writeln!(out, " {}{}{}", INDENT, INDENT, name)?;
+        } else {
+            writeln!(out, " {}{}{}x {}", INDENT, INDENT, c, name)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_science(out: &mut String, science: &Science) -> fmt::Result {
+    writeln!(
+        out,
+        " Science: {} Mits with {}, {:.1} EC",
+        science.amount, science.antenna, science.charge
+    )?;
+    writeln!(out)?;
+
+    let format_time = |t: Option<f64>| t.map(format_duration).unwrap_or_else(|| "NA".to_owned());
+    writeln!(out, " |          Section          |   @Min   |   @Max   |")?;
+    writeln!(out, " |:--------------------------|---------:|---------:|")?;
+    for s in &science.sections {
+        writeln!(
+            out,
+            " | {:<25} | {:>8} | {:>8} |",
+            s.section,
+            format_time(s.at_min),
+            format_time(s.at_max),
+        )?;
+    }
+    writeln!(out)
+}
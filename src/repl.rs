@@ -0,0 +1,334 @@
+//! Interactive mode to edit both endpoints and print the report again.
+
+use anyhow::{Error, Result};
+use clap::{App, ArgMatches, SubCommand};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use crate::catalog::Catalog;
+use crate::cli::{parse_modifiers, print_antennas, print_report};
+use crate::endpoint::{split_antenna_arg, unknown_antenna_message, AntennaSet, EndpointBuilder};
+use crate::metric::parse_distance;
+use crate::{DEFAULT_FROM, DEFAULT_TO};
+
+pub const NAME: &str = "interactive";
+
+const PROMPT: &str = "commnet> ";
+
+const COMMANDS: &[&str] = &[
+    "from", "to", "show", "dist", "antennas", "history", "help", "quit", "exit",
+];
+const ENDPOINT_COMMANDS: &[&str] = &["add", "set", "remove", "clear"];
+
+const HELP: &str = "\
+Commands:
+    from add <ANTENNAS>       Add comma-separated antennas, like '2:HG-5, RA-2'
+    from set <ANTENNAS>       Replace the antennas
+    from remove <ANTENNA>     Remove an antenna
+    from clear                Remove all antennas
+    to ...                    Same as 'from' for 'to'
+    show                      Print the report
+    dist <DISTANCE>           Set the distance of the report, or 'dist off'
+    antennas                  Print available antennas
+    history                   Print the command history
+    help                      Print this help
+    quit, exit                Quit";
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME).about("Edit endpoints and print the report interactively")
+}
+
+struct Session<'a> {
+    matches: &'a ArgMatches<'a>,
+    antennas: &'a Catalog,
+    from: AntennaSet,
+    to: AntennaSet,
+    distance: Option<f64>,
+}
+
+pub fn interactive(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let mut editor = Editor::<ReplHelper>::new();
+    editor.set_helper(Some(ReplHelper::new(antennas)));
+
+    let mut session = Session {
+        matches,
+        antennas,
+        from: AntennaSet::default(),
+        to: AntennaSet::default(),
+        distance: None,
+    };
+
+    println!("Type 'help' for commands. Tab completes antenna names.");
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(l) => l,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line);
+
+        match line {
+            "quit" | "exit" => break,
+            "history" => {
+                for (i, h) in editor.history().iter().enumerate() {
+                    println!("{:>4}  {}", i + 1, h);
+                }
+            }
+            _ => {
+                if let Err(e) = session.execute(line) {
+                    eprintln!("Error: {}", e);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Session<'_> {
+    fn execute(&mut self, line: &str) -> Result<()> {
+        let (command, rest) = split_word(line);
+        match command {
+            "from" | "to" => self.edit(command, rest),
+            "show" => self.show(),
+            "dist" => {
+                self.distance = match rest {
+                    "" | "off" => None,
+                    d => Some(parse_distance(d)?),
+                };
+                self.show()
+            }
+            "antennas" => {
+                print_antennas(self.antennas);
+                Ok(())
+            }
+            "help" => {
+                println!("{}", HELP);
+                Ok(())
+            }
+            _ => Err(Error::msg(format!(
+                "unknown command '{}'; type 'help' for commands",
+                command
+            ))),
+        }
+    }
+
+    fn edit(&mut self, side: &str, args: &str) -> Result<()> {
+        let (op, rest) = split_word(args);
+        let antennas = self.antennas;
+        let target = if side == "from" {
+            &mut self.from
+        } else {
+            &mut self.to
+        };
+
+        match op {
+            "add" | "set" => {
+                // Validate all antennas before changing the endpoint.
+                let mut added = Vec::new();
+                for spec in rest.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    let (count, name) = split_antenna_arg(spec)?;
+                    let antenna = antennas
+                        .get(name)
+                        .ok_or_else(|| Error::msg(unknown_antenna_message(antennas, name)))?;
+                    added.push((antenna.name.clone(), count));
+                }
+                if added.is_empty() {
+                    return Err(Error::msg(format!("usage: {} {} <ANTENNAS>", side, op)));
+                }
+
+                if op == "set" {
+                    target.clear();
+                }
+                for (name, count) in added {
+                    target.add(&name, count);
+                }
+            }
+            "remove" => {
+                let antenna = antennas
+                    .get(rest)
+                    .ok_or_else(|| Error::msg(unknown_antenna_message(antennas, rest)))?;
+                if !target.remove_all(&antenna.name) {
+                    return Err(Error::msg(format!("'{}' has no {}", side, antenna.name)));
+                }
+            }
+            "clear" => target.clear(),
+            _ => {
+                return Err(Error::msg(format!(
+                    "usage: {} add|set|remove|clear ...",
+                    side
+                )))
+            }
+        }
+
+        self.show()
+    }
+
+    fn show(&self) -> Result<()> {
+        let from_specs = self.from.specs();
+        let to_specs = self.to.specs();
+        let builder = EndpointBuilder::new(self.antennas).modifiers(parse_modifiers(self.matches)?);
+        let from = builder.build(from_specs.iter().map(String::as_str), DEFAULT_FROM)?;
+        let to = builder.build(to_specs.iter().map(String::as_str), DEFAULT_TO)?;
+
+        print_report(self.matches, self.antennas, &from, &to, self.distance)
+    }
+}
+
+/// Splits the first word of `s` from the rest.
+fn split_word(s: &str) -> (&str, &str) {
+    let s = s.trim();
+    match s.find(char::is_whitespace) {
+        Some(i) => (&s[..i], s[i..].trim()),
+        None => (s, ""),
+    }
+}
+
+/// Completion of commands, and antenna names and aliases.
+struct ReplHelper {
+    names: Vec<String>,
+}
+
+impl ReplHelper {
+    fn new(antennas: &Catalog) -> Self {
+        let mut names = Vec::new();
+        for a in antennas.iter() {
+            names.push(a.name.clone());
+            names.extend(a.aliases.iter().cloned());
+        }
+        ReplHelper { names }
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.completions(&line[..pos]))
+    }
+}
+
+impl ReplHelper {
+    /// Start of the word being completed at the end of `head`, and the candidates for it.
+    fn completions(&self, head: &str) -> (usize, Vec<String>) {
+        let pos = head.len();
+        let words: Vec<&str> = head.split_whitespace().collect();
+        let partial = !head.ends_with(char::is_whitespace);
+
+        // Still typing the command, or the operation of 'from' or 'to'.
+        let choices = match (words.len(), partial) {
+            (0, _) | (1, true) => Some(COMMANDS),
+            (1, false) | (2, true) if words[0] == "from" || words[0] == "to" => {
+                Some(ENDPOINT_COMMANDS)
+            }
+            _ => None,
+        };
+        if let Some(choices) = choices {
+            let word = if partial {
+                words.last().copied().unwrap_or("")
+            } else {
+                ""
+            };
+            let candidates = choices
+                .iter()
+                .filter(|c| c.starts_with(word))
+                .map(|c| (*c).to_owned())
+                .collect();
+            return (pos - word.len(), candidates);
+        }
+
+        if words.first().map_or(true, |w| *w != "from" && *w != "to") {
+            return (pos, Vec::new());
+        }
+
+        // The antenna being typed starts after the last comma and the count.
+        let (_, args) = split_word(head);
+        let (_, args) = split_word(args);
+        let args_start = head.trim_end().len() - args.len();
+        let mut start = head
+            .rfind(',')
+            .map_or(args_start, |i| i + 1)
+            .max(args_start);
+        if let Some(colon) = head[start..].find(':') {
+            if head[start..start + colon].trim().parse::<usize>().is_ok() {
+                start += colon + 1;
+            }
+        }
+        start += head[start..].len() - head[start..].trim_start().len();
+
+        let prefix = head[start..].to_lowercase();
+        let candidates = self
+            .names
+            .iter()
+            .filter(|n| n.to_lowercase().starts_with(&prefix))
+            .cloned()
+            .collect();
+        (start, candidates)
+    }
+}
+
+impl Hinter for ReplHelper {}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete(head: &str) -> (usize, Vec<String>) {
+        let helper = ReplHelper {
+            names: vec![
+                "HG-5".to_owned(),
+                "HighGainAntenna5".to_owned(),
+                "RA-2".to_owned(),
+            ],
+        };
+        helper.completions(head)
+    }
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|s| (*s).to_owned()).collect()
+    }
+
+    #[test]
+    fn completes_commands() {
+        assert_eq!(complete(""), (0, strings(COMMANDS)));
+        assert_eq!(complete("fr"), (0, strings(&["from"])));
+        assert_eq!(complete("to "), (3, strings(ENDPOINT_COMMANDS)));
+        assert_eq!(complete("to a"), (3, strings(&["add"])));
+        assert_eq!(complete("dist 1"), (6, Vec::new()));
+    }
+
+    #[test]
+    fn completes_antennas_after_count_and_comma() {
+        let all = strings(&["HG-5", "HighGainAntenna5", "RA-2"]);
+        assert_eq!(complete("to add "), (7, all.clone()));
+        assert_eq!(
+            complete("from set h"),
+            (9, strings(&["HG-5", "HighGainAntenna5"]))
+        );
+        assert_eq!(complete("to add 2:HG"), (9, strings(&["HG-5"])));
+        assert_eq!(complete("to add 2: hg"), (10, strings(&["HG-5"])));
+        assert_eq!(complete("to add 2:HG-5, r"), (15, strings(&["RA-2"])));
+        assert_eq!(complete("to add 2:HG-5,"), (14, all.clone()));
+        assert_eq!(complete("  to  add   ra"), (12, strings(&["RA-2"])));
+        assert_eq!(complete("to add HG-5, "), (13, all));
+    }
+}
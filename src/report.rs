@@ -0,0 +1,277 @@
+//! Report of the signal strengths between two endpoints.
+//!
+//! ```ignore
+//! let catalog = Catalog::new();
+//! let report = ReportBuilder::new(&catalog)
+//!     .distance(Some(12.0e9))
+//!     .build_specs(vec!["DSN Lv.3"], vec!["2:HG-5"])?;
+//! print!("{}", markdown::render(&report));
+//! ```
+
+use std::sync::Arc;
+
+use anyhow::{Error, Result};
+
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+
+use crate::catalog::Catalog;
+use crate::endpoint::{is_ground_station_endpoint, EndpointBuilder, Modifiers, Role};
+use crate::geometry::{Section, Sections};
+use crate::science::{Science, Transmitter};
+use crate::signal::{AtDistance, SectionStrength};
+use crate::{DEFAULT_FROM, DEFAULT_TO};
+
+/// Endpoint as shown in a report.
+#[derive(Debug, Clone)]
+pub struct EndpointSummary {
+    pub endpoint_type: String,
+    pub power: f64,
+    pub ground_station: bool,
+    /// Antenna names and counts.
+    pub antennas: Vec<(String, usize)>,
+}
+
+impl EndpointSummary {
+    pub fn new(antennas: &Catalog, endpoint: &Endpoint) -> Self {
+        let mut antennas = Vec::new();
+        for (a, c) in endpoint.antenna_counts() {
+            antennas.push((a.name.clone(), c));
+        }
+
+        EndpointSummary {
+            endpoint_type: endpoint.endpoint_type().to_string(),
+            power: endpoint.power(),
+            ground_station: is_ground_station_endpoint(antennas, endpoint),
+            antennas,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub from: EndpointSummary,
+    pub to: EndpointSummary,
+    pub modifiers: Modifiers,
+    pub max_distance: f64,
+    pub at_distance: Option<AtDistance>,
+    pub sections: Vec<SectionStrength>,
+    pub science: Option<Science>,
+}
+
+/// Report of a named candidate design of `to`, to compare with others.
+#[derive(Debug)]
+pub struct Design {
+    pub name: String,
+    pub report: Report,
+}
+
+/// Builder of reports. Cloning it is cheap, to change a few options for each report.
+#[derive(Clone)]
+pub struct ReportBuilder<'a> {
+    antennas: &'a Catalog,
+    modifiers: Modifiers,
+    lenient: bool,
+    from_role: Option<Role>,
+    to_role: Option<Role>,
+    sections: Arc<Sections>,
+    extra_sections: Vec<Section>,
+    distance: Option<f64>,
+    science: Option<f64>,
+}
+
+impl<'a> ReportBuilder<'a> {
+    pub fn new(antennas: &'a Catalog) -> Self {
+        ReportBuilder {
+            antennas,
+            modifiers: Modifiers::default(),
+            lenient: false,
+            from_role: None,
+            to_role: None,
+            sections: Arc::new(Sections::stock()),
+            extra_sections: Vec::new(),
+            distance: None,
+            science: None,
+        }
+    }
+
+    /// Modifiers shown in the report, and applied to the endpoints of `build_specs`.
+    pub fn modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Skips unknown antennas of the specifiers with a warning instead of failing.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Role of the `from` endpoint built from specifiers.
+    pub fn from_role(mut self, role: Option<Role>) -> Self {
+        self.from_role = role;
+        self
+    }
+
+    /// Role of the `to` endpoint built from specifiers.
+    pub fn to_role(mut self, role: Option<Role>) -> Self {
+        self.to_role = role;
+        self
+    }
+
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = Arc::new(sections);
+        self
+    }
+
+    /// Adds a section after the others, like the distances between two orbits.
+    pub fn section(mut self, section: Section) -> Self {
+        self.extra_sections.push(section);
+        self
+    }
+
+    /// Distance in meters to also report the strength at.
+    pub fn distance(mut self, distance: Option<f64>) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Amount of science in Mits to report the transmission of.
+    pub fn science(mut self, amount: Option<f64>) -> Self {
+        self.science = amount;
+        self
+    }
+
+    /// Builds the report between endpoints of antenna specifiers like `2:HG-5`,
+    /// each defaulting to the default antenna if empty.
+    pub fn build_specs<'s>(
+        &self,
+        from: impl IntoIterator<Item = &'s str>,
+        to: impl IntoIterator<Item = &'s str>,
+    ) -> Result<Report> {
+        let from = self.build_from(from)?;
+        let to = self.build_to(to)?;
+        self.build(&from, &to)
+    }
+
+    /// Builds the `from` endpoint of antenna specifiers, with the modifiers, role and leniency.
+    pub fn build_from<'s>(&self, specs: impl IntoIterator<Item = &'s str>) -> Result<Endpoint> {
+        self.endpoint_builder(self.from_role)
+            .build(specs.into_iter(), DEFAULT_FROM)
+    }
+
+    /// Builds the `to` endpoint of antenna specifiers, with the modifiers, role and leniency.
+    pub fn build_to<'s>(&self, specs: impl IntoIterator<Item = &'s str>) -> Result<Endpoint> {
+        self.endpoint_builder(self.to_role)
+            .build(specs.into_iter(), DEFAULT_TO)
+    }
+
+    fn endpoint_builder(&self, role: Option<Role>) -> EndpointBuilder<'a> {
+        EndpointBuilder::new(self.antennas)
+            .modifiers(self.modifiers)
+            .lenient(self.lenient)
+            .role(role)
+    }
+
+    /// Builds the reports of designs like `probeA=2:HG-5,RA-2` from the same endpoint,
+    /// failing on a duplicate name.
+    pub fn build_designs<'s>(
+        &self,
+        from: &Endpoint,
+        designs: impl IntoIterator<Item = &'s str>,
+    ) -> Result<Vec<Design>> {
+        let mut reports: Vec<Design> = Vec::new();
+        for d in designs {
+            let (name, spec) = parse_design(d)?;
+            if reports.iter().any(|r| r.name == name) {
+                return Err(Error::msg(format!("duplicate design '{}'", name)));
+            }
+            let to = self.build_to(spec.split(',').map(str::trim))?;
+            reports.push(Design {
+                name: name.to_owned(),
+                report: self.build(from, &to)?,
+            });
+        }
+        Ok(reports)
+    }
+
+    pub fn build(&self, from: &Endpoint, to: &Endpoint) -> Result<Report> {
+        let max_distance = from.range_to(to).max_distance();
+        let at_distance = self.distance.map(|d| AtDistance::new(max_distance, d));
+
+        let mut sections = self.sections.strengths(from, to);
+        for s in &self.extra_sections {
+            sections.push(SectionStrength::new(
+                s.name.clone(),
+                max_distance,
+                s.min,
+                s.max,
+            ));
+        }
+
+        let science = match self.science {
+            Some(amount) => Some(transmission(self.antennas, from, to, amount, &sections)?),
+            None => None,
+        };
+
+        Ok(Report {
+            from: EndpointSummary::new(self.antennas, from),
+            to: EndpointSummary::new(self.antennas, to),
+            modifiers: self.modifiers,
+            max_distance,
+            at_distance,
+            sections,
+            science,
+        })
+    }
+}
+
+/// Splits a design like `NAME=ANTENNAS` into its trimmed name and antenna specifiers.
+pub fn parse_design(s: &str) -> Result<(&str, &str)> {
+    let invalid = || Error::msg(format!("design should be like NAME=ANTENNAS, but '{}'", s));
+    let i = s.find('=').ok_or_else(invalid)?;
+    let (name, spec) = (s[..i].trim(), s[i + 1..].trim());
+    if name.is_empty() || spec.is_empty() {
+        return Err(invalid());
+    }
+    Ok((name, spec))
+}
+
+/// Parses an amount of science in Mits, which should be positive.
+pub fn parse_science(s: &str) -> Result<f64> {
+    let amount = s.trim().parse::<f64>().map_err(|_| invalid_science(s))?;
+    check_science(amount)
+}
+
+fn check_science(amount: f64) -> Result<f64> {
+    if amount > 0.0 && amount.is_finite() {
+        Ok(amount)
+    } else {
+        Err(invalid_science(amount))
+    }
+}
+
+fn invalid_science(value: impl std::fmt::Display) -> Error {
+    Error::msg(format!(
+        "science should be a positive number of Mits, but {}",
+        value
+    ))
+}
+
+/// Transmission of `amount` Mits by `to`, or by `from` if `to` is a ground station.
+fn transmission(
+    antennas: &Catalog,
+    from: &Endpoint,
+    to: &Endpoint,
+    amount: f64,
+    strengths: &[SectionStrength],
+) -> Result<Science> {
+    check_science(amount)?;
+    let vessel = if is_ground_station_endpoint(antennas, to) {
+        from
+    } else {
+        to
+    };
+    let transmitter = Transmitter::find(antennas, vessel)
+        .ok_or_else(|| Error::msg("no antenna with packet values to transmit science"))?;
+    Ok(Science::new(&transmitter, amount, strengths))
+}
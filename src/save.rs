@@ -0,0 +1,112 @@
+//! Vessels of a KSP save file (`persistent.sfs`).
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+
+use crate::catalog::Catalog;
+use crate::config_node::{self, Node};
+use crate::parts::{collect_antennas, PartAntennas};
+
+pub struct Save {
+    pub vessels: Vec<Vessel>,
+    /// Upgrade level of the tracking station in `0.0..=1.0`, if recorded.
+    pub tracking_station_level: Option<f64>,
+}
+
+pub struct Vessel {
+    pub name: String,
+    /// Type like `Probe`, `Relay` or `Debris`, if recorded.
+    pub vessel_type: Option<String>,
+    pub orbit: Option<Orbit>,
+    parts: Vec<Node>,
+}
+
+/// Orbit of a vessel around the body of index `body`.
+#[derive(Debug, Clone, Copy)]
+pub struct Orbit {
+    pub body: usize,
+    pub sma: f64,
+    pub eccentricity: f64,
+}
+
+impl Orbit {
+    fn from_node(node: &Node) -> Option<Orbit> {
+        Some(Orbit {
+            body: node.value("REF")?.parse().ok()?,
+            sma: node.value("SMA")?.parse().ok()?,
+            eccentricity: node.value("ECC")?.parse().ok()?,
+        })
+    }
+
+    /// Apoapsis from the center of the body. Escape orbits count as their semi-major axis.
+    pub fn apoapsis(&self) -> f64 {
+        if self.eccentricity < 1.0 {
+            self.sma * (1.0 + self.eccentricity)
+        } else {
+            self.sma.abs()
+        }
+    }
+}
+
+impl Save {
+    pub fn load(path: &Path) -> Result<Save> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?;
+        Save::parse(&source).map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Parses the content of a save file.
+    pub fn parse(source: &str) -> Result<Save> {
+        let root = config_node::parse(source)?;
+
+        let game = root
+            .nodes_named("GAME")
+            .next()
+            .ok_or_else(|| Error::msg("no GAME node"))?;
+
+        let mut vessels = Vec::new();
+        for flight_state in game.nodes_named("FLIGHTSTATE") {
+            for v in flight_state.nodes_named("VESSEL") {
+                vessels.push(Vessel {
+                    name: v.value("name").unwrap_or_default().to_owned(),
+                    vessel_type: v.value("type").map(str::to_owned),
+                    orbit: v.nodes_named("ORBIT").next().and_then(Orbit::from_node),
+                    parts: v.nodes_named("PART").cloned().collect(),
+                });
+            }
+        }
+
+        let tracking_station_level = game
+            .nodes
+            .iter()
+            .filter(|n| n.name == "SCENARIO")
+            .filter(|n| n.value("name") == Some("ScenarioUpgradeableFacilities"))
+            .flat_map(|n| n.nodes_named("SpaceCenter/TrackingStation"))
+            .find_map(|n| n.value("lvl").and_then(|v| v.parse().ok()));
+
+        Ok(Save {
+            vessels,
+            tracking_station_level,
+        })
+    }
+
+    pub fn vessel(&self, name: &str) -> Result<&Vessel> {
+        self.vessels
+            .iter()
+            .find(|v| v.name == name)
+            .or_else(|| {
+                self.vessels
+                    .iter()
+                    .find(|v| v.name.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| Error::msg(format!("vessel '{}' not found in save", name)))
+    }
+}
+
+impl Vessel {
+    pub fn antennas(&self, catalog: &Catalog) -> PartAntennas {
+        collect_antennas(catalog, self.parts.iter())
+    }
+}
@@ -0,0 +1,402 @@
+//! Scenario files of named cases to check at once.
+//!
+//! A scenario is a TOML, YAML or JSON file with a list of `case` entries:
+//!
+//! ```toml
+//! [[case]]
+//! name = "Mun relay"
+//! from = ["DSN Lv.2"]
+//! to = ["2:HG-5"]
+//! distances = ["12Mm", "84Mm"]
+//! min_strength = 20
+//!
+//! [[case]]
+//! name = "Duna probe"
+//! from = ["RA-100"]
+//! to = ["HG-5"]
+//! to_role = "direct"
+//! sections = ["Kerbin - Duna"]
+//! ```
+//!
+//! A case passes if the strength at every distance, and at the max distance
+//! of every section, is at least `min_strength` percent, or in range if it is
+//! not given. `from` and `to` default like `--from` and `--to`.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::endpoint::Role;
+use crate::metric::parse_distance;
+use crate::report::ReportBuilder;
+use crate::signal::strength_at;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioFile {
+    #[serde(default)]
+    case: Vec<Case>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Case {
+    pub name: String,
+    #[serde(default)]
+    pub from: Vec<String>,
+    #[serde(default)]
+    pub to: Vec<String>,
+    pub from_role: Option<String>,
+    pub to_role: Option<String>,
+    #[serde(default)]
+    pub distances: Vec<Distance>,
+    #[serde(default)]
+    pub sections: Vec<String>,
+    /// Required strength in percent.
+    #[serde(default)]
+    pub min_strength: f64,
+}
+
+/// Distance in meters, or with a metric prefix like `12Mm`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Distance {
+    Meters(f64),
+    Text(String),
+}
+
+impl Distance {
+    pub fn meters(&self) -> Result<f64> {
+        match self {
+            Distance::Meters(m) if m.is_finite() && *m >= 0.0 => Ok(*m),
+            Distance::Meters(m) => Err(Error::msg(format!(
+                "distance should be a non-negative number of meters, but {}",
+                m
+            ))),
+            Distance::Text(s) => parse_distance(s),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    pub cases: Vec<Case>,
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Scenario> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))?;
+
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        Scenario::parse(&source, &extension)
+            .map_err(|e| Error::msg(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Parses a scenario in the format of a file extension, TOML if unknown.
+    fn parse(source: &str, extension: &str) -> Result<Scenario> {
+        let file: ScenarioFile = match extension {
+            "json" => serde_json::from_str(source)?,
+            "yaml" | "yml" => serde_yaml::from_str(source)?,
+            _ => toml::from_str(source)?,
+        };
+
+        let scenario = Scenario { cases: file.case };
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.cases.is_empty() {
+            return Err(Error::msg("no case"));
+        }
+
+        for (i, case) in self.cases.iter().enumerate() {
+            if self.cases[..i].iter().any(|c| c.name == case.name) {
+                return Err(Error::msg(format!("duplicate case '{}'", case.name)));
+            }
+            if case.distances.is_empty() && case.sections.is_empty() {
+                return Err(Error::msg(format!(
+                    "case '{}' has no distance or section to check",
+                    case.name
+                )));
+            }
+            if !(0.0..=100.0).contains(&case.min_strength) {
+                return Err(Error::msg(format!(
+                    "min_strength of case '{}' should be in 0..=100, but {}",
+                    case.name, case.min_strength
+                )));
+            }
+            for d in &case.distances {
+                d.meters()
+                    .map_err(|e| Error::msg(format!("case '{}': {}", case.name, e)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Strength of a case at a distance or section.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub label: String,
+    pub strength: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub checks: Vec<Check>,
+    /// Required strength in `0.0..=1.0`.
+    pub min_strength: f64,
+}
+
+impl Outcome {
+    /// Check with the lowest strength, out of range first.
+    pub fn weakest(&self) -> Option<&Check> {
+        self.checks.iter().min_by(|a, b| {
+            let a = a.strength.unwrap_or(-1.0);
+            let b = b.strength.unwrap_or(-1.0);
+            a.total_cmp(&b)
+        })
+    }
+
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|c| c.strength.map_or(false, |s| s >= self.min_strength))
+    }
+}
+
+impl Case {
+    /// Evaluates the case with the modifiers and sections of a report builder.
+    pub fn evaluate(&self, report: &ReportBuilder) -> Result<Outcome> {
+        let role = |r: &Option<String>| -> Result<Option<Role>> {
+            r.as_deref().map(str::parse).transpose()
+        };
+        let report = report
+            .clone()
+            .from_role(role(&self.from_role)?)
+            .to_role(role(&self.to_role)?)
+            .build_specs(
+                self.from.iter().map(String::as_str),
+                self.to.iter().map(String::as_str),
+            )?;
+
+        let mut checks = Vec::new();
+        for d in &self.distances {
+            let d = d.meters()?;
+            checks.push(Check {
+                label: format!("{}m", MetricPrefix(d)),
+                strength: strength_at(report.max_distance, d),
+            });
+        }
+
+        for name in &self.sections {
+            let s = report
+                .sections
+                .iter()
+                .find(|s| s.section.eq_ignore_ascii_case(name))
+                .ok_or_else(|| Error::msg(format!("unknown section '{}'", name)))?;
+            checks.push(Check {
+                label: s.section.clone(),
+                strength: s.at_max,
+            });
+        }
+
+        Ok(Outcome {
+            checks,
+            min_strength: self.min_strength / 100.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::catalog::Catalog;
+
+    const TOML: &str = r#"
+[[case]]
+name = "Mun relay"
+from = ["DSN Lv.2"]
+to = ["2:HG-5"]
+distances = ["12Mm", "84Mm"]
+min_strength = 20
+
+[[case]]
+name = "Duna probe"
+to = ["HG-5"]
+to_role = "direct"
+sections = ["Kerbin - Duna"]
+"#;
+
+    const YAML: &str = r#"
+case:
+  - name: Mun relay
+    from: [DSN Lv.2]
+    to: ["2:HG-5"]
+    distances: [12Mm, 84.0e6]
+    min_strength: 20
+  - name: Duna probe
+    to: [HG-5]
+    to_role: direct
+    sections: [Kerbin - Duna]
+"#;
+
+    const JSON: &str = r#"{
+  "case": [
+    {
+      "name": "Mun relay",
+      "from": ["DSN Lv.2"],
+      "to": ["2:HG-5"],
+      "distances": ["12Mm", 84.0e6],
+      "min_strength": 20
+    },
+    {
+      "name": "Duna probe",
+      "to": ["HG-5"],
+      "to_role": "direct",
+      "sections": ["Kerbin - Duna"]
+    }
+  ]
+}"#;
+
+    fn case(name: &str) -> Case {
+        Case {
+            name: name.to_owned(),
+            from: Vec::new(),
+            to: Vec::new(),
+            from_role: None,
+            to_role: None,
+            distances: vec![Distance::Meters(0.0)],
+            sections: Vec::new(),
+            min_strength: 0.0,
+        }
+    }
+
+    fn validate(cases: Vec<Case>) -> String {
+        Scenario { cases }.validate().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_formats() {
+        for (source, extension) in &[(TOML, "toml"), (YAML, "yaml"), (JSON, "json")] {
+            let scenario = Scenario::parse(source, extension).unwrap();
+            assert_eq!(scenario.cases.len(), 2, "{}", extension);
+
+            let mun = &scenario.cases[0];
+            assert_eq!(mun.name, "Mun relay");
+            assert_eq!(mun.from, vec!["DSN Lv.2"]);
+            assert_eq!(mun.to, vec!["2:HG-5"]);
+            let distances: Vec<f64> = mun.distances.iter().map(|d| d.meters().unwrap()).collect();
+            assert_eq!(distances, vec![12.0e6, 84.0e6], "{}", extension);
+            assert_eq!(mun.min_strength, 20.0);
+
+            let duna = &scenario.cases[1];
+            assert!(duna.from.is_empty());
+            assert_eq!(duna.to_role.as_deref(), Some("direct"));
+            assert_eq!(duna.sections, vec!["Kerbin - Duna"]);
+            assert_eq!(duna.min_strength, 0.0);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let source = "[[case]]\nname = \"a\"\ndistances = [0]\nmax_strength = 1\n";
+        assert!(Scenario::parse(source, "toml").is_err());
+    }
+
+    #[test]
+    fn validates_cases() {
+        assert_eq!(validate(Vec::new()), "no case");
+        assert_eq!(validate(vec![case("a"), case("a")]), "duplicate case 'a'");
+
+        let mut c = case("a");
+        c.distances.clear();
+        assert_eq!(
+            validate(vec![c]),
+            "case 'a' has no distance or section to check"
+        );
+
+        let mut c = case("a");
+        c.min_strength = 120.0;
+        assert_eq!(
+            validate(vec![c]),
+            "min_strength of case 'a' should be in 0..=100, but 120"
+        );
+
+        let mut c = case("a");
+        c.distances = vec![Distance::Text("12 parsecs".to_owned())];
+        assert_eq!(validate(vec![c]), "case 'a': invalid distance: 12 parsecs");
+
+        let mut c = case("a");
+        c.distances = vec![Distance::Meters(-1.0)];
+        assert_eq!(
+            validate(vec![c]),
+            "case 'a': distance should be a non-negative number of meters, but -1"
+        );
+
+        let mut c = case("a");
+        c.distances = vec![Distance::Meters(f64::NAN)];
+        assert!(Scenario { cases: vec![c] }.validate().is_err());
+    }
+
+    #[test]
+    fn outcome_passes_and_weakest() {
+        let check = |label: &str, strength| Check {
+            label: label.to_owned(),
+            strength,
+        };
+
+        let outcome = Outcome {
+            checks: vec![check("near", Some(0.9)), check("far", Some(0.3))],
+            min_strength: 0.2,
+        };
+        assert!(outcome.passed());
+        assert_eq!(outcome.weakest().unwrap().label, "far");
+
+        let outcome = Outcome {
+            min_strength: 0.5,
+            ..outcome
+        };
+        assert!(!outcome.passed());
+
+        let outcome = Outcome {
+            checks: vec![check("near", Some(0.9)), check("out", None)],
+            min_strength: 0.0,
+        };
+        assert!(!outcome.passed());
+        assert_eq!(outcome.weakest().unwrap().label, "out");
+    }
+
+    #[test]
+    fn evaluates_case() {
+        let catalog = Catalog::new();
+        let report = ReportBuilder::new(&catalog);
+
+        let mut c = case("a");
+        c.from = vec!["DSN Lv.3".to_owned()];
+        c.to = vec!["HG-5".to_owned()];
+        c.distances = vec![Distance::Meters(0.0), Distance::Meters(1.0e30)];
+        c.sections = vec!["kerbin - mun".to_owned()];
+        let outcome = c.evaluate(&report).unwrap();
+
+        assert_eq!(outcome.checks.len(), 3);
+        assert_eq!(outcome.checks[0].strength, Some(1.0));
+        assert_eq!(outcome.checks[1].strength, None);
+        assert_eq!(outcome.checks[2].label, "Kerbin - Mun");
+        assert!(!outcome.passed());
+
+        c.sections = vec!["Kerbin - Nowhere".to_owned()];
+        let err = c.evaluate(&report).unwrap_err();
+        assert_eq!(err.to_string(), "unknown section 'Kerbin - Nowhere'");
+    }
+}
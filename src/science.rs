@@ -0,0 +1,97 @@
+//! Transmission time and electric charge of science data.
+//!
+//! Like the game, a vessel transmits with its single antenna of the highest
+//! data rate, whatever other antennas it has. The data rate is scaled by the
+//! signal strength, and the electric charge is paid per packet.
+
+use serde::Serialize;
+
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+
+use crate::catalog::{Catalog, Packet};
+use crate::signal::SectionStrength;
+
+/// Antenna which transmits for an endpoint.
+#[derive(Debug, Clone)]
+pub struct Transmitter {
+    pub antenna: String,
+    pub packet: Packet,
+}
+
+impl Transmitter {
+    /// Antenna with the highest data rate among the antennas of `endpoint`, if any has packets.
+    pub fn find(catalog: &Catalog, endpoint: &Endpoint) -> Option<Transmitter> {
+        let mut best: Option<Transmitter> = None;
+        for (a, _) in endpoint.antenna_counts() {
+            let packet = match catalog.entry(&a.name).and_then(|e| e.packet) {
+                Some(p) => p,
+                None => continue,
+            };
+            if best
+                .as_ref()
+                .map_or(true, |b| packet.rate() > b.packet.rate())
+            {
+                best = Some(Transmitter {
+                    antenna: a.name.clone(),
+                    packet,
+                });
+            }
+        }
+        best
+    }
+
+    /// Number of packets for `amount` Mits.
+    pub fn packets(&self, amount: f64) -> f64 {
+        (amount / self.packet.size).ceil()
+    }
+
+    /// Electric charge for `amount` Mits.
+    pub fn charge(&self, amount: f64) -> f64 {
+        self.packets(amount) * self.packet.cost
+    }
+
+    /// Seconds to transmit `amount` Mits at `strength`, or `None` if out of range.
+    pub fn time(&self, amount: f64, strength: Option<f64>) -> Option<f64> {
+        match strength {
+            Some(s) if s > 0.0 => Some(self.packets(amount) * self.packet.interval / s),
+            _ => None,
+        }
+    }
+}
+
+/// Transmission of science data for each section.
+#[derive(Debug, Clone, Serialize)]
+pub struct Science {
+    /// Amount of data in Mits.
+    pub amount: f64,
+    pub antenna: String,
+    /// Electric charge, which does not depend on the strength.
+    pub charge: f64,
+    pub sections: Vec<ScienceSection>,
+}
+
+/// Transmission times in seconds at the min and max distances of a section.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScienceSection {
+    pub section: String,
+    pub at_min: Option<f64>,
+    pub at_max: Option<f64>,
+}
+
+impl Science {
+    pub fn new(transmitter: &Transmitter, amount: f64, strengths: &[SectionStrength]) -> Self {
+        Science {
+            amount,
+            antenna: transmitter.antenna.clone(),
+            charge: transmitter.charge(amount),
+            sections: strengths
+                .iter()
+                .map(|s| ScienceSection {
+                    section: s.section.clone(),
+                    at_min: transmitter.time(amount, s.at_min),
+                    at_max: transmitter.time(amount, s.at_max),
+                })
+                .collect(),
+        }
+    }
+}
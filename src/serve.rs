@@ -0,0 +1,159 @@
+//! Local HTTP server with the antennas and the distance report in JSON.
+//!
+//! The server listens only on `127.0.0.1` and handles one request at a time.
+//!
+//! - `GET /antennas` lists the antennas of the catalog.
+//! - `POST /range` returns the distance report of the JSON output for a body like
+//!
+//! ```json
+//! { "from": ["DSN Lv.3"], "to": ["2:HG-5", "RA-2"], "distance": 12000000.0 }
+//! ```
+//!
+//! `from` and `to` take the same antenna specifiers as `--from` and `--to`,
+//! and default to the same antennas if empty or absent. `distance` is in
+//! meters and optional, and so are `from_role` and `to_role`.
+//!
+//! Errors are returned with a status of 4xx and a body like `{ "error": "..." }`.
+
+use std::io::Read;
+
+use anyhow::{Error, Result};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::catalog::Catalog;
+use crate::cli::{load_sections, parse_modifiers, parse_role};
+use crate::render::json::{JsonAntenna, JsonReport};
+use crate::report::ReportBuilder;
+
+pub const NAME: &str = "serve";
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME)
+        .about("Serve the antennas and distance reports as JSON over HTTP on localhost")
+        .arg(
+            Arg::with_name("port")
+                .short("p")
+                .long("port")
+                .takes_value(true)
+                .default_value("8080")
+                .help("Port to listen on, or 0 for any free port"),
+        )
+}
+
+/// Body of `POST /range`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RangeRequest {
+    #[serde(default)]
+    from: Vec<String>,
+    #[serde(default)]
+    to: Vec<String>,
+    distance: Option<f64>,
+    from_role: Option<String>,
+    to_role: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+struct Service<'a> {
+    antennas: &'a Catalog,
+    report: ReportBuilder<'a>,
+}
+
+pub fn serve(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let port: u16 = matches
+        .value_of("port")
+        .unwrap()
+        .parse()
+        .map_err(|_| Error::msg("port should be a number in 0..=65535"))?;
+
+    let service = Service {
+        antennas,
+        report: ReportBuilder::new(antennas)
+            .modifiers(parse_modifiers(matches)?)
+            .sections(load_sections(matches)?),
+    };
+
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| Error::msg(e.to_string()))?;
+    println!("Listening on http://{}", server.server_addr());
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = service.handle(&mut request);
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                    .expect("valid header"),
+            );
+        if let Err(e) = request.respond(response) {
+            eprintln!("Warning: failed to respond: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+impl Service<'_> {
+    /// Returns the status code and the JSON body of the response.
+    fn handle(&self, request: &mut Request) -> (u16, String) {
+        let method = request.method().clone();
+        let path = request.url().split('?').next().unwrap_or("").to_owned();
+        let result = match (method, path.as_str()) {
+            (Method::Get, "/antennas") => self.antennas_json(),
+            (Method::Post, "/range") => {
+                let mut body = String::new();
+                match request.as_reader().read_to_string(&mut body) {
+                    Ok(_) => self.range_json(&body).map_err(|e| (400, e.to_string())),
+                    Err(e) => Err((400, format!("failed to read the body: {}", e))),
+                }
+            }
+            (_, "/antennas") | (_, "/range") => Err((405, "method not allowed".to_owned())),
+            _ => Err((404, format!("no such endpoint '{}'", path))),
+        };
+
+        match result {
+            Ok(body) => (200, body),
+            Err((status, error)) => (status, error_json(error)),
+        }
+    }
+
+    fn antennas_json(&self) -> Result<String, (u16, String)> {
+        let list: Vec<JsonAntenna> = self.antennas.iter().map(JsonAntenna::new).collect();
+        serde_json::to_string(&list).map_err(|e| (500, e.to_string()))
+    }
+
+    fn range_json(&self, body: &str) -> Result<String> {
+        let req: RangeRequest = serde_json::from_str(body)
+            .map_err(|e| Error::msg(format!("invalid request body: {}", e)))?;
+
+        if let Some(d) = req.distance {
+            if d < 0.0 || !d.is_finite() {
+                return Err(Error::msg(format!(
+                    "distance should be a non-negative number of meters, but {}",
+                    d
+                )));
+            }
+        }
+
+        let report = self
+            .report
+            .clone()
+            .from_role(parse_role(req.from_role.as_deref())?)
+            .to_role(parse_role(req.to_role.as_deref())?)
+            .distance(req.distance)
+            .build_specs(
+                req.from.iter().map(String::as_str),
+                req.to.iter().map(String::as_str),
+            )?;
+        Ok(serde_json::to_string(&JsonReport::new(&report))?)
+    }
+}
+
+fn error_json(error: String) -> String {
+    serde_json::to_string(&ErrorBody { error }).unwrap_or_else(|_| "{}".to_owned())
+}
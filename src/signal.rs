@@ -0,0 +1,70 @@
+use ksp_commnet_calculator_core::distance::Strength;
+
+#[derive(Debug, Clone, Copy)]
+pub struct AtDistance {
+    pub distance: f64,
+    pub strength: Option<f64>,
+}
+
+impl AtDistance {
+    pub fn new(max_distance: f64, distance: f64) -> Self {
+        AtDistance {
+            distance,
+            strength: strength_at(max_distance, distance),
+        }
+    }
+}
+
+/// Signal strength at `distance` for a link of `max_distance`, or `None` if out of range.
+pub fn strength_at(max_distance: f64, distance: f64) -> Option<f64> {
+    if distance > max_distance {
+        return None;
+    }
+
+    let x = 1.0 - distance / max_distance;
+    Some((3.0 - 2.0 * x) * x * x)
+}
+
+/// Longest distance with at least `strength` for a link of `max_distance`.
+pub fn distance_for_strength(max_distance: f64, strength: f64) -> f64 {
+    // The strength is monotonic in x, so bisect for it.
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..60 {
+        let x = (lo + hi) / 2.0;
+        if (3.0 - 2.0 * x) * x * x < strength {
+            lo = x;
+        } else {
+            hi = x;
+        }
+    }
+    max_distance * (1.0 - hi)
+}
+
+/// Strengths at the min and max distances of a section.
+#[derive(Debug, Clone)]
+pub struct SectionStrength {
+    pub section: String,
+    pub at_min: Option<f64>,
+    pub at_max: Option<f64>,
+}
+
+impl SectionStrength {
+    /// Strengths between `min` and `max` for a link of `max_distance`.
+    pub fn new(section: String, max_distance: f64, min: f64, max: f64) -> Self {
+        SectionStrength {
+            section,
+            at_min: strength_at(max_distance, min),
+            at_max: strength_at(max_distance, max),
+        }
+    }
+}
+
+impl From<&Strength> for SectionStrength {
+    fn from(strength: &Strength) -> Self {
+        SectionStrength {
+            section: strength.section.to_string(),
+            at_min: strength.at_min,
+            at_max: strength.at_max,
+        }
+    }
+}
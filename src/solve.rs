@@ -0,0 +1,548 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use anyhow::{Error, Result};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use ksp_commnet_calculator_core::antenna::Antenna;
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+
+use crate::catalog::{Catalog, Entry};
+use crate::cli::{load_sections, parse_modifiers};
+use crate::endpoint::{is_ground_station, EndpointBuilder, Modifiers};
+use crate::geometry::Sections;
+use crate::render::format_strength;
+use crate::DEFAULT_FROM;
+
+pub const NAME: &str = "solve";
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME)
+        .about("Search the smallest antenna sets that reach a section")
+        .arg(
+            Arg::with_name("from")
+                .short("f")
+                .long("from")
+                .multiple(true)
+                .takes_value(true)
+                .default_value(DEFAULT_FROM),
+        )
+        .arg(
+            Arg::with_name("section")
+                .short("s")
+                .long("section")
+                .takes_value(true)
+                .required(true)
+                .help("Target section, as printed in the report"),
+        )
+        .arg(
+            Arg::with_name("at")
+                .long("at")
+                .takes_value(true)
+                .possible_values(&["min", "max"])
+                .default_value("max")
+                .help("Use the @Min or @Max distance of the section"),
+        )
+        .arg(
+            Arg::with_name("min-strength")
+                .long("min-strength")
+                .takes_value(true)
+                .value_name("PERCENT")
+                .default_value("0")
+                .help("Required strength in percent (e.g. 50 or 50%)"),
+        )
+        .arg(
+            Arg::with_name("max-count")
+                .long("max-count")
+                .takes_value(true)
+                .default_value("4")
+                .help("Maximum number of antennas on the vessel"),
+        )
+        .arg(
+            Arg::with_name("top")
+                .long("top")
+                .takes_value(true)
+                .default_value("5")
+                .help("Number of configurations to print"),
+        )
+        .arg(
+            Arg::with_name("rank")
+                .long("rank")
+                .takes_value(true)
+                .possible_values(&["count", "mass", "cost"])
+                .default_value("count")
+                .help(
+                    "Ranking of configurations; mass and cost use antennas with those values only",
+                ),
+        )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rank {
+    Count,
+    Mass,
+    Cost,
+}
+
+impl Rank {
+    fn value(self, entry: &Entry) -> Option<f64> {
+        match self {
+            Rank::Count => Some(1.0),
+            Rank::Mass => entry.mass,
+            Rank::Cost => entry.cost,
+        }
+    }
+}
+
+pub fn solve(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let modifiers = parse_modifiers(matches)?;
+    let from = EndpointBuilder::new(antennas)
+        .modifiers(modifiers)
+        .build(matches.values_of("from").unwrap_or_default(), DEFAULT_FROM)?;
+
+    let section = matches.value_of("section").unwrap();
+    let at_max = matches.value_of("at") == Some("max");
+    let min_strength = parse_percent(matches.value_of("min-strength").unwrap())?;
+    let max_count: usize = matches.value_of("max-count").unwrap().parse()?;
+    let top: usize = matches.value_of("top").unwrap().parse()?;
+    let rank = match matches.value_of("rank") {
+        Some("mass") => Rank::Mass,
+        Some("cost") => Rank::Cost,
+        _ => Rank::Count,
+    };
+
+    let sections = load_sections(matches)?;
+    check_section(&sections, &from, section)?;
+
+    let (candidates, excluded) = candidates(antennas, rank);
+    if excluded > 0 {
+        eprintln!(
+            "Warning: {} antennas without {} are excluded",
+            excluded,
+            matches.value_of("rank").unwrap()
+        );
+    }
+
+    if top == 0 {
+        return Err(Error::msg("top should be at least 1"));
+    }
+
+    let mut search = Search {
+        candidates: &candidates,
+        sections: &sections,
+        from: &from,
+        section,
+        at_max,
+        min_strength,
+        modifiers: &modifiers,
+        antennas,
+        top,
+        kept: BinaryHeap::new(),
+        picks: Vec::new(),
+    };
+    search.visit(0, max_count, 0.0);
+    let solutions = search.kept.into_sorted_vec();
+
+    if solutions.is_empty() {
+        return Err(Error::msg(format!(
+            "no configuration of up to {} antennas reaches '{}'",
+            max_count, section
+        )));
+    }
+
+    println!();
+    match rank {
+        Rank::Count => {
+            println!(" | Rank | Count | Strength | Antennae");
+            println!(" |-----:|------:|---------:|:---------");
+        }
+        Rank::Mass => {
+            println!(" | Rank | Count |   Mass   | Strength | Antennae");
+            println!(" |-----:|------:|---------:|---------:|:---------");
+        }
+        Rank::Cost => {
+            println!(" | Rank | Count |   Cost   | Strength | Antennae");
+            println!(" |-----:|------:|---------:|---------:|:---------");
+        }
+    }
+    for (i, s) in solutions.iter().take(top).enumerate() {
+        let score = if rank == Rank::Count {
+            String::new()
+        } else {
+            format!(" {:>8.3} |", s.score)
+        };
+        println!(
+            " | {:>4} | {:>5} |{} {:>8} | {}",
+            i + 1,
+            s.total,
+            score,
+            format_strength(Some(s.strength)),
+            describe_antennas(&s.to),
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Antennas of vessels with their values of the rank, and the number of antennas without one.
+fn candidates(antennas: &Catalog, rank: Rank) -> (Vec<(&Antenna, f64)>, usize) {
+    let mut candidates = Vec::new();
+    let mut excluded = 0;
+    for a in antennas.iter().filter(|a| !is_ground_station(antennas, a)) {
+        match antennas.entry(&a.name).and_then(|e| rank.value(e)) {
+            Some(v) => candidates.push((a, v)),
+            None => excluded += 1,
+        }
+    }
+    (candidates, excluded)
+}
+
+struct Solution {
+    total: usize,
+    score: f64,
+    strength: f64,
+    to: Endpoint,
+}
+
+impl Solution {
+    /// Better solutions are less: lower score, fewer antennas, then higher strength.
+    fn rank(&self, other: &Solution) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then(self.total.cmp(&other.total))
+            .then(other.strength.total_cmp(&self.strength))
+    }
+}
+
+impl PartialEq for Solution {
+    fn eq(&self, other: &Solution) -> bool {
+        self.rank(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Solution {}
+
+impl PartialOrd for Solution {
+    fn partial_cmp(&self, other: &Solution) -> Option<Ordering> {
+        Some(self.rank(other))
+    }
+}
+
+impl Ord for Solution {
+    fn cmp(&self, other: &Solution) -> Ordering {
+        self.rank(other)
+    }
+}
+
+/// Depth-first search over antenna counts, keeping only the best `top` solutions.
+///
+/// Each multiset of candidates is visited once, as picks of increasing candidate
+/// index. The scores of candidates are not negative, so a branch is pruned once
+/// its partial score exceeds the worst kept solution.
+struct Search<'a> {
+    candidates: &'a [(&'a Antenna, f64)],
+    sections: &'a Sections,
+    from: &'a Endpoint,
+    section: &'a str,
+    at_max: bool,
+    min_strength: f64,
+    modifiers: &'a Modifiers,
+    antennas: &'a Catalog,
+    top: usize,
+    /// Max-heap with the worst kept solution on top.
+    kept: BinaryHeap<Solution>,
+    /// Candidate indices and counts of the current branch.
+    picks: Vec<(usize, usize)>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, start: usize, remaining: usize, score: f64) {
+        for i in start..self.candidates.len() {
+            let (_, v) = self.candidates[i];
+            for c in 1..=remaining {
+                let score = score + v * c as f64;
+                if self.is_pruned(score) {
+                    break;
+                }
+
+                self.picks.push((i, c));
+                self.evaluate(score);
+                self.visit(i + 1, remaining - c, score);
+                self.picks.pop();
+            }
+        }
+    }
+
+    fn is_pruned(&self, score: f64) -> bool {
+        self.kept.len() >= self.top && self.kept.peek().map_or(false, |w| score > w.score)
+    }
+
+    fn evaluate(&mut self, score: f64) {
+        let mut to = Endpoint::new();
+        let mut total = 0;
+        for &(i, c) in &self.picks {
+            to.add_antenna(self.modifiers.apply(self.antennas, self.candidates[i].0), c);
+            total += c;
+        }
+
+        let strength =
+            match section_strength(self.sections, self.from, &to, self.section, self.at_max) {
+                Some(s) if s >= self.min_strength => s,
+                _ => return,
+            };
+
+        let solution = Solution {
+            total,
+            score,
+            strength,
+            to,
+        };
+        if self.kept.len() < self.top {
+            self.kept.push(solution);
+        } else if self.kept.peek().map_or(false, |w| solution < *w) {
+            self.kept.pop();
+            self.kept.push(solution);
+        }
+    }
+}
+
+fn check_section(sections: &Sections, from: &Endpoint, section: &str) -> Result<()> {
+    let strengths = sections.strengths(from, from);
+    if strengths.iter().any(|s| same_section(&s.section, section)) {
+        return Ok(());
+    }
+
+    let mut msg = format!("unknown section '{}'; available sections:", section);
+    for s in &strengths {
+        msg.push_str(&format!("\n    {}", s.section));
+    }
+    Err(Error::msg(msg))
+}
+
+fn section_strength(
+    sections: &Sections,
+    from: &Endpoint,
+    to: &Endpoint,
+    section: &str,
+    at_max: bool,
+) -> Option<f64> {
+    let strengths = sections.strengths(from, to);
+    let s = strengths
+        .iter()
+        .find(|s| same_section(&s.section, section))?;
+
+    if at_max {
+        s.at_max
+    } else {
+        s.at_min
+    }
+}
+
+fn same_section(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Parses a percent like `50` or `50%` in `0..=100` into `0.0..=1.0`.
+pub fn parse_percent(s: &str) -> Result<f64> {
+    let v: f64 = s
+        .trim()
+        .trim_end_matches('%')
+        .trim_end()
+        .parse()
+        .map_err(|_| Error::msg(format!("invalid percent: {}", s)))?;
+    if !(0.0..=100.0).contains(&v) {
+        return Err(Error::msg(format!(
+            "percent should be in 0..=100, but {}",
+            s
+        )));
+    }
+    Ok(v / 100.0)
+}
+
+fn describe_antennas(endpoint: &Endpoint) -> String {
+    let mut parts = Vec::new();
+    for (a, c) in endpoint.antenna_counts() {
+        if c == 1 {
+            parts.push(a.name.clone());
+        } else {
+            parts.push(format!("{}x {}", c, a.name));
+        }
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECTION: &str = "Kerbin - Duna";
+
+    /// Score, total and strength of the best solutions, as compared between searches.
+    type Key = (f64, usize, f64);
+
+    fn key(s: &Solution) -> Key {
+        (s.score, s.total, s.strength)
+    }
+
+    fn search(
+        antennas: &Catalog,
+        rank: Rank,
+        min_strength: f64,
+        max_count: usize,
+        top: usize,
+    ) -> Vec<Key> {
+        let (candidates, _) = candidates(antennas, rank);
+        let sections = Sections::stock();
+        let from = EndpointBuilder::new(antennas)
+            .build(vec!["DSN Lv.3"], DEFAULT_FROM)
+            .unwrap();
+        let modifiers = Modifiers::default();
+
+        let mut search = Search {
+            candidates: &candidates,
+            sections: &sections,
+            from: &from,
+            section: SECTION,
+            at_max: true,
+            min_strength,
+            modifiers: &modifiers,
+            antennas,
+            top,
+            kept: BinaryHeap::new(),
+            picks: Vec::new(),
+        };
+        search.visit(0, max_count, 0.0);
+        search.kept.into_sorted_vec().iter().map(key).collect()
+    }
+
+    /// Every multiset of candidates of up to `max_count` antennas, sorted like the search.
+    fn brute_force(
+        antennas: &Catalog,
+        rank: Rank,
+        min_strength: f64,
+        max_count: usize,
+    ) -> Vec<Key> {
+        fn enumerate(
+            n: usize,
+            remaining: usize,
+            counts: &mut Vec<usize>,
+            all: &mut Vec<Vec<usize>>,
+        ) {
+            if counts.len() == n {
+                if counts.iter().sum::<usize>() > 0 {
+                    all.push(counts.clone());
+                }
+                return;
+            }
+            for c in 0..=remaining {
+                counts.push(c);
+                enumerate(n, remaining - c, counts, all);
+                counts.pop();
+            }
+        }
+
+        let (candidates, _) = candidates(antennas, rank);
+        let sections = Sections::stock();
+        let from = EndpointBuilder::new(antennas)
+            .build(vec!["DSN Lv.3"], DEFAULT_FROM)
+            .unwrap();
+
+        let mut all = Vec::new();
+        enumerate(candidates.len(), max_count, &mut Vec::new(), &mut all);
+
+        let mut solutions = Vec::new();
+        for counts in all {
+            let mut to = Endpoint::new();
+            let mut score = 0.0;
+            for (&(a, v), &c) in candidates.iter().zip(&counts) {
+                if c > 0 {
+                    to.add_antenna(a.clone(), c);
+                    score += v * c as f64;
+                }
+            }
+            if let Some(strength) = section_strength(&sections, &from, &to, SECTION, true) {
+                if strength >= min_strength {
+                    solutions.push(Solution {
+                        total: counts.iter().sum(),
+                        score,
+                        strength,
+                        to,
+                    });
+                }
+            }
+        }
+        solutions.sort();
+        solutions.iter().map(key).collect()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let antennas = Catalog::new();
+        for &rank in &[Rank::Count, Rank::Mass, Rank::Cost] {
+            for &min_strength in &[0.0, 0.5] {
+                let all = brute_force(&antennas, rank, min_strength, 3);
+                assert!(all.len() > 10, "{:?}", rank);
+
+                for &top in &[1, 5, 10] {
+                    let found = search(&antennas, rank, min_strength, 3, top);
+                    assert_eq!(found.len(), top);
+                    for (f, b) in found.iter().zip(&all) {
+                        // Scores are sums in another order, so compare them with a tolerance.
+                        assert!((f.0 - b.0).abs() < 1e-9, "{:?} {:?}", f, b);
+                        assert_eq!((f.1, f.2), (b.1, b.2), "{:?} top {}", rank, top);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_every_solution_without_pruning() {
+        let antennas = Catalog::new();
+        let all = brute_force(&antennas, Rank::Count, 0.0, 2);
+        let found = search(&antennas, Rank::Count, 0.0, 2, usize::MAX);
+        assert_eq!(found, all);
+    }
+
+    #[test]
+    fn ranks_ties_by_total_then_strength() {
+        let antennas = Catalog::new();
+        let solution = |total, score, strength| Solution {
+            total,
+            score,
+            strength,
+            to: Endpoint::new(),
+        };
+
+        let mut solutions = vec![
+            solution(2, 1.0, 0.9),
+            solution(1, 1.0, 0.5),
+            solution(1, 1.0, 0.8),
+            solution(3, 0.5, 0.1),
+        ];
+        solutions.sort();
+        let keys: Vec<Key> = solutions.iter().map(key).collect();
+        assert_eq!(
+            keys,
+            vec![(0.5, 3, 0.1), (1.0, 1, 0.8), (1.0, 1, 0.5), (1.0, 2, 0.9)]
+        );
+
+        // Count ranks by the number of antennas, so score and total agree.
+        for (score, total, _) in search(&antennas, Rank::Count, 0.0, 3, 10) {
+            assert_eq!(score, total as f64);
+        }
+    }
+
+    #[test]
+    fn parses_percent() {
+        assert_eq!(parse_percent("50").unwrap(), 0.5);
+        assert_eq!(parse_percent(" 25 % ").unwrap(), 0.25);
+        assert_eq!(parse_percent("0").unwrap(), 0.0);
+        assert_eq!(parse_percent("100%").unwrap(), 1.0);
+
+        for invalid in &["", "%", "abc", "NaN", "inf", "-1", "100.5", "150%"] {
+            assert!(parse_percent(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
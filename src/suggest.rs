@@ -0,0 +1,107 @@
+use crate::catalog::Catalog;
+
+const MAX_SUGGESTIONS: usize = 5;
+
+pub fn suggest_antennas(antennas: &Catalog, name: &str) -> Vec<String> {
+    let key = normalize(name);
+
+    let mut candidates: Vec<(usize, String)> = Vec::new();
+    for a in antennas.iter() {
+        let mut best: Option<usize> = None;
+        for n in std::iter::once(&a.name).chain(a.aliases.iter()) {
+            let d = distance(&key, &normalize(n));
+            if best.map(|b| d < b).unwrap_or(true) {
+                best = Some(d);
+            }
+        }
+
+        if let Some(d) = best {
+            if d <= threshold(&key) {
+                candidates.push((d, display_name(&a.name, &a.aliases)));
+            }
+        }
+    }
+
+    candidates.sort();
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, s)| s)
+        .collect()
+}
+
+fn display_name(name: &str, aliases: &[String]) -> String {
+    if aliases.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{} ({})", name, aliases.join(", "))
+    }
+}
+
+fn threshold(key: &str) -> usize {
+    (key.chars().count() / 3).max(2)
+}
+
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_ignores_case_and_spaces() {
+        assert_eq!(normalize(" Communotron 16 "), "communotron16");
+        assert_eq!(normalize("HG-5"), "hg-5");
+    }
+
+    #[test]
+    fn distance_counts_edits() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("abc", ""), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("hg5", "hg-5"), 1);
+    }
+
+    #[test]
+    fn threshold_grows_with_length() {
+        assert_eq!(threshold("ra2"), 2);
+        assert_eq!(threshold("communotron16"), 4);
+    }
+
+    #[test]
+    fn suggests_close_names() {
+        let catalog = Catalog::new();
+        let suggestions = suggest_antennas(&catalog, "hg5");
+        assert!(suggestions.iter().any(|s| s.starts_with("HG-5")));
+        assert!(suggestions.len() <= MAX_SUGGESTIONS);
+    }
+
+    #[test]
+    fn suggests_nothing_for_unrelated_names() {
+        let catalog = Catalog::new();
+        assert!(suggest_antennas(&catalog, "zzzzzzzzzz").is_empty());
+    }
+}
@@ -0,0 +1,409 @@
+//! Strength of a link over time, with both endpoints on heliocentric orbits
+//! or bodies.
+
+use anyhow::{Error, Result};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::bodies::System;
+use crate::calendar::{format_date, format_duration, parse_date, parse_duration};
+use crate::catalog::Catalog;
+use crate::cli::{load_system, parse_modifiers, print_endpoint};
+use crate::endpoint::EndpointBuilder;
+use crate::ephemeris::{body_position, position, Elements};
+use crate::metric::parse_distance;
+use crate::render::delimited::{record, Delimiter};
+use crate::render::format_strength;
+use crate::signal::strength_at;
+use crate::solve::parse_percent;
+use crate::{DEFAULT_FROM, DEFAULT_TO};
+
+pub const NAME: &str = "timeline";
+
+const MAX_SAMPLES: f64 = 1e6;
+
+const SPARKS: &[char] = &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME)
+        .about("Print the strength of a link over time")
+        .arg(
+            Arg::with_name("from")
+                .short("f")
+                .long("from")
+                .multiple(true)
+                .takes_value(true)
+                .default_value(DEFAULT_FROM),
+        )
+        .arg(
+            Arg::with_name("to")
+                .short("t")
+                .long("to")
+                .multiple(true)
+                .takes_value(true),
+        )
+        .args(&side_args(
+            [
+                "from-body",
+                "from-sma",
+                "from-ecc",
+                "from-lpe",
+                "from-anomaly",
+            ],
+            "Body of 'from' (default: the home body)",
+        ))
+        .args(&side_args(
+            ["to-body", "to-sma", "to-ecc", "to-lpe", "to-anomaly"],
+            "Body of 'to'",
+        ))
+        .arg(
+            Arg::with_name("start")
+                .long("start")
+                .takes_value(true)
+                .value_name("DATE")
+                .default_value("Y1 D1")
+                .help("Start date in the Kerbin calendar"),
+        )
+        .arg(
+            Arg::with_name("end")
+                .long("end")
+                .takes_value(true)
+                .value_name("DATE")
+                .default_value("Y3 D1")
+                .help("End date in the Kerbin calendar"),
+        )
+        .arg(
+            Arg::with_name("step")
+                .long("step")
+                .takes_value(true)
+                .value_name("DURATION")
+                .default_value("1d")
+                .help("Time step (e.g. 6h, 1d, 1y)"),
+        )
+        .arg(
+            Arg::with_name("min-strength")
+                .long("min-strength")
+                .takes_value(true)
+                .value_name("PERCENT")
+                .default_value("0")
+                .help("Threshold of the time above it (e.g. 50 or 50%)"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["markdown", "csv", "tsv"])
+                .default_value("markdown")
+                .help("Output format"),
+        )
+        .arg(
+            Arg::with_name("no-header")
+                .long("no-header")
+                .help("Omit the header row of csv/tsv output"),
+        )
+        .arg(
+            Arg::with_name("width")
+                .long("width")
+                .takes_value(true)
+                .default_value("60")
+                .help("Width of the sparkline"),
+        )
+}
+
+/// Args of one endpoint: a body, or a heliocentric orbit.
+fn side_args(names: [&'static str; 5], body_help: &'static str) -> Vec<Arg<'static, 'static>> {
+    let [body, sma, ecc, lpe, anomaly] = names;
+    vec![
+        Arg::with_name(body)
+            .long(body)
+            .takes_value(true)
+            .value_name("BODY")
+            .conflicts_with(sma)
+            .help(body_help),
+        Arg::with_name(sma)
+            .long(sma)
+            .takes_value(true)
+            .value_name("DISTANCE")
+            .help("Semi-major axis of a heliocentric orbit, instead of a body"),
+        Arg::with_name(ecc)
+            .long(ecc)
+            .takes_value(true)
+            .value_name("ECCENTRICITY")
+            .requires(sma)
+            .help("Eccentricity of the heliocentric orbit"),
+        Arg::with_name(lpe)
+            .long(lpe)
+            .takes_value(true)
+            .value_name("DEGREES")
+            .requires(sma)
+            .help("Longitude of periapsis of the heliocentric orbit"),
+        Arg::with_name(anomaly)
+            .long(anomaly)
+            .takes_value(true)
+            .value_name("DEGREES")
+            .requires(sma)
+            .help("Mean anomaly of the heliocentric orbit at Y1 D1"),
+    ]
+}
+
+/// Point of the timeline.
+struct Sample {
+    time: f64,
+    distance: f64,
+    strength: Option<f64>,
+}
+
+pub fn timeline(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let modifiers = parse_modifiers(matches)?;
+    let system = load_system(matches)?;
+
+    let from = EndpointBuilder::new(antennas)
+        .modifiers(modifiers)
+        .build(matches.values_of("from").unwrap_or_default(), DEFAULT_FROM)?;
+    let to = EndpointBuilder::new(antennas)
+        .modifiers(modifiers)
+        .build(matches.values_of("to").unwrap_or_default(), DEFAULT_TO)?;
+    let max_distance = from.range_to(&to).max_distance();
+
+    let from_orbit = side_orbit(matches, "from", &system)?;
+    let to_orbit = match side_orbit(matches, "to", &system)? {
+        Some(o) => o,
+        None => return Err(Error::msg("--to-body or --to-sma is required")),
+    };
+
+    let start = parse_date(matches.value_of("start").unwrap())?;
+    let end = parse_date(matches.value_of("end").unwrap())?;
+    let step = parse_duration(matches.value_of("step").unwrap())?;
+    let threshold = parse_percent(matches.value_of("min-strength").unwrap())?;
+    let width: usize = matches.value_of("width").unwrap().parse()?;
+    if end <= start {
+        return Err(Error::msg("end should be after start"));
+    }
+    if (end - start) / step > MAX_SAMPLES {
+        return Err(Error::msg(format!(
+            "too many steps; use a step longer than {}",
+            format_duration((end - start) / MAX_SAMPLES)
+        )));
+    }
+
+    // From the sample index, as adding the step may not move a large start date.
+    let count = ((end - start) / step).floor() as usize;
+    let mut samples = Vec::with_capacity(count + 1);
+    for i in 0..=count {
+        let t = start + i as f64 * step;
+        let (x1, y1) = side_position(&system, from_orbit.as_ref(), t)?;
+        let (x2, y2) = side_position(&system, Some(&to_orbit), t)?;
+        let distance = (x1 - x2).hypot(y1 - y2);
+        samples.push(Sample {
+            time: t,
+            distance,
+            strength: strength_at(max_distance, distance),
+        });
+    }
+    if samples.is_empty() {
+        return Err(Error::msg("no sample between start and end"));
+    }
+
+    match matches.value_of("format") {
+        Some("csv") => print_samples(&samples, Delimiter::Comma, !matches.is_present("no-header")),
+        Some("tsv") => print_samples(&samples, Delimiter::Tab, !matches.is_present("no-header")),
+        _ => {
+            println!();
+            println!(" From:");
+            print_endpoint(antennas, &from, &modifiers);
+            println!(" To:");
+            print_endpoint(antennas, &to, &modifiers);
+            println!();
+            println!(" Max distance: {}m", MetricPrefix(max_distance));
+            println!(
+                " Period: {} - {}, step {}",
+                format_date(start),
+                format_date(end),
+                format_duration(step)
+            );
+            println!();
+            print_summary(&samples, threshold, step, width);
+        }
+    }
+
+    Ok(())
+}
+
+/// Orbit of the `side` endpoint, or `None` for the center of the home body.
+fn side_orbit(matches: &ArgMatches, side: &str, system: &System) -> Result<Option<Elements>> {
+    if let Some(name) = matches.value_of(format!("{}-body", side)) {
+        let body = system
+            .get(name)
+            .ok_or_else(|| Error::msg(format!("unknown body '{}'", name)))?;
+        return Ok(Elements::of_body(body));
+    }
+
+    let sma = match matches.value_of(format!("{}-sma", side)) {
+        Some(s) => parse_distance(s)?,
+        None if side == "from" => return Ok(Elements::of_body(system.home())),
+        None => return Ok(None),
+    };
+    let number = |arg: &str| -> Result<f64> {
+        match matches.value_of(format!("{}-{}", side, arg)) {
+            Some(s) => s
+                .parse()
+                .map_err(|_| Error::msg(format!("invalid {}-{}: {}", side, arg, s))),
+            None => Ok(0.0),
+        }
+    };
+    let eccentricity = number("ecc")?;
+    if !(0.0..1.0).contains(&eccentricity) {
+        return Err(Error::msg(format!(
+            "eccentricity should be in 0 <= e < 1, but {}",
+            eccentricity
+        )));
+    }
+
+    let star = system
+        .iter()
+        .find(|b| b.parent.is_none())
+        .ok_or_else(|| Error::msg("no star in the system"))?;
+    Ok(Some(Elements {
+        parent: star.name.clone(),
+        sma,
+        eccentricity,
+        longitude_of_periapsis: number("lpe")?.to_radians(),
+        mean_anomaly: number("anomaly")?.to_radians(),
+    }))
+}
+
+fn side_position(system: &System, orbit: Option<&Elements>, t: f64) -> Result<(f64, f64)> {
+    match orbit {
+        Some(o) => position(system, o, t),
+        None => body_position(system, system.home(), t),
+    }
+}
+
+fn print_samples(samples: &[Sample], delimiter: Delimiter, header: bool) {
+    if header {
+        println!(
+            "{}",
+            record(&["time", "date", "distance", "strength"], delimiter)
+        );
+    }
+    for s in samples {
+        let time = s.time.to_string();
+        let date = format_date(s.time);
+        let distance = s.distance.to_string();
+        let strength = s.strength.map(|v| v.to_string()).unwrap_or_default();
+        println!(
+            "{}",
+            record(&[&time, &date, &distance, &strength], delimiter)
+        );
+    }
+}
+
+fn print_summary(samples: &[Sample], threshold: f64, step: f64, width: usize) {
+    let above = |s: &Sample| s.strength.map(|v| v >= threshold).unwrap_or(false);
+
+    println!(" Strength: |{}|", sparkline(samples, width));
+    println!();
+
+    let closest = samples
+        .iter()
+        .map(|s| s.distance)
+        .fold(f64::INFINITY, f64::min);
+    let farthest = samples.iter().map(|s| s.distance).fold(0.0, f64::max);
+    println!(
+        " Distance: {}m - {}m",
+        MetricPrefix(closest),
+        MetricPrefix(farthest)
+    );
+
+    let count = samples.iter().filter(|s| above(s)).count();
+    println!(
+        " Time above {}: {}",
+        format_strength(Some(threshold)),
+        format_strength(Some(count as f64 / samples.len() as f64))
+    );
+
+    // Longest run of samples below the threshold, as (first index, length).
+    let mut longest = (0, 0);
+    let mut run = 0;
+    for (i, s) in samples.iter().enumerate() {
+        if above(s) {
+            run = 0;
+        } else {
+            run += 1;
+            if run > longest.1 {
+                longest = (i + 1 - run, run);
+            }
+        }
+    }
+    if longest.1 > 0 {
+        println!(
+            " Longest time below: {} from {}",
+            format_duration(longest.1 as f64 * step),
+            format_date(samples[longest.0].time)
+        );
+    }
+    println!();
+}
+
+/// Sparkline of the weakest strength in each column, blank if out of range.
+fn sparkline(samples: &[Sample], width: usize) -> String {
+    if samples.is_empty() {
+        return String::new();
+    }
+    let width = width.clamp(1, samples.len());
+    (0..width)
+        .map(|col| {
+            let begin = col * samples.len() / width;
+            let end = (col + 1) * samples.len() / width;
+            let weakest = samples[begin..end].iter().map(|s| s.strength).fold(
+                Some(1.0),
+                |acc: Option<f64>, s| match (acc, s) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    _ => None,
+                },
+            );
+            match weakest {
+                Some(v) => {
+                    SPARKS[((v * (SPARKS.len() - 1) as f64).round() as usize).min(SPARKS.len() - 1)]
+                }
+                None => ' ',
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(strengths: &[Option<f64>]) -> Vec<Sample> {
+        strengths
+            .iter()
+            .enumerate()
+            .map(|(i, &strength)| Sample {
+                time: i as f64,
+                distance: 0.0,
+                strength,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sparkline_buckets_weakest() {
+        let s = samples(&[Some(1.0), Some(0.0), Some(1.0), Some(1.0), None, Some(1.0)]);
+
+        assert_eq!(sparkline(&s, 6), "█▁██ █");
+        assert_eq!(sparkline(&s, 3), "▁█ ");
+        assert_eq!(sparkline(&s, 1), " ");
+    }
+
+    #[test]
+    fn sparkline_width_is_clamped() {
+        let s = samples(&[Some(0.5), Some(1.0)]);
+
+        assert_eq!(sparkline(&s, 0), "▅");
+        assert_eq!(sparkline(&s, 60), "▅█");
+        assert_eq!(sparkline(&[], 60), "");
+    }
+}
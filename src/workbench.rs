@@ -0,0 +1,566 @@
+//! Full-screen terminal workbench to edit both endpoints with a live report.
+
+use std::io::{self, Stdout};
+
+use anyhow::{Error, Result};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use tui::backend::{Backend, CrosstermBackend};
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Row, Table};
+use tui::{Frame, Terminal};
+
+use ksp_commnet_calculator_core::antenna::Antenna;
+use ksp_commnet_calculator_core::endpoint::Endpoint;
+use ksp_commnet_calculator_core::util::MetricPrefix;
+
+use crate::catalog::Catalog;
+use crate::cli::{load_sections, parse_modifiers, parse_role};
+use crate::endpoint::{is_relay, AntennaSet, EndpointBuilder, Modifiers, Role};
+use crate::geometry::Sections;
+use crate::render::format_strength;
+use crate::{DEFAULT_FROM, DEFAULT_TO};
+
+pub const NAME: &str = "workbench";
+
+const KEYS: &str =
+    "type: search  Up/Down: select  Enter/Right: add  Left/Del: remove  Tab: switch  Esc: quit";
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name(NAME)
+        .about("Edit endpoints in a full-screen terminal UI")
+        .arg(
+            Arg::with_name("from-role")
+                .long("from-role")
+                .takes_value(true)
+                .possible_values(&["relay", "direct"])
+                .help("Count only relay antennas of 'from' if relay"),
+        )
+        .arg(
+            Arg::with_name("to-role")
+                .long("to-role")
+                .takes_value(true)
+                .possible_values(&["relay", "direct"])
+                .help("Count only relay antennas of 'to' if relay"),
+        )
+        .arg(Arg::with_name("lenient").long("lenient").help(
+            "Use the default antenna instead of failing if an endpoint has no usable antenna",
+        ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    From,
+    To,
+}
+
+struct Workbench<'a> {
+    antennas: &'a Catalog,
+    modifiers: Modifiers,
+    sections: Sections,
+    lenient: bool,
+    from_role: Option<Role>,
+    to_role: Option<Role>,
+    search: String,
+    list: ListState,
+    focus: Side,
+    from: AntennaSet,
+    to: AntennaSet,
+}
+
+/// Restores the terminal even on errors.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+    }
+}
+
+pub fn workbench(matches: &ArgMatches, antennas: &Catalog) -> Result<()> {
+    let mut bench = Workbench::new(antennas);
+    bench.modifiers = parse_modifiers(matches)?;
+    bench.sections = load_sections(matches)?;
+    bench.lenient = matches.is_present("lenient");
+    bench.from_role = parse_role(matches.value_of("from-role"))?;
+    bench.to_role = parse_role(matches.value_of("to-role"))?;
+
+    enable_raw_mode()?;
+    let _guard = TerminalGuard;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal: Terminal<CrosstermBackend<Stdout>> =
+        Terminal::new(CrosstermBackend::new(stdout))?;
+
+    loop {
+        terminal.draw(|f| bench.draw(f))?;
+
+        if let Event::Key(key) = event::read()? {
+            if !bench.handle_key(key) {
+                break;
+            }
+        }
+    }
+
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+impl<'a> Workbench<'a> {
+    fn new(antennas: &'a Catalog) -> Self {
+        let mut list = ListState::default();
+        list.select(Some(0));
+        Workbench {
+            antennas,
+            modifiers: Modifiers::default(),
+            sections: Sections::stock(),
+            lenient: false,
+            from_role: None,
+            to_role: None,
+            search: String::new(),
+            list,
+            focus: Side::To,
+            from: AntennaSet::default(),
+            to: AntennaSet::default(),
+        }
+    }
+
+    /// Antennas matching the search, by name or alias.
+    fn filtered(&self) -> Vec<&Antenna> {
+        let query = self.search.to_lowercase();
+        self.antennas
+            .iter()
+            .filter(|a| {
+                a.name.to_lowercase().contains(&query)
+                    || a.aliases
+                        .iter()
+                        .any(|al| al.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    fn selected(&self) -> Option<String> {
+        let i = self.list.selected()?;
+        self.filtered().get(i).map(|a| a.name.clone())
+    }
+
+    fn focused(&mut self) -> &mut AntennaSet {
+        match self.focus {
+            Side::From => &mut self.from,
+            Side::To => &mut self.to,
+        }
+    }
+
+    /// Handles a key, and returns false to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let len = self.filtered().len();
+        let selected = self.list.selected().unwrap_or(0);
+
+        match key.code {
+            KeyCode::Char('c') | KeyCode::Char('q')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                return false
+            }
+            KeyCode::Esc if self.search.is_empty() => return false,
+            KeyCode::Esc => self.search.clear(),
+            KeyCode::Up => self.list.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down => self
+                .list
+                .select(Some((selected + 1).min(len.saturating_sub(1)))),
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Side::From => Side::To,
+                    Side::To => Side::From,
+                }
+            }
+            KeyCode::Enter | KeyCode::Right => {
+                if let Some(name) = self.selected() {
+                    self.focused().add(&name, 1);
+                }
+            }
+            KeyCode::Left | KeyCode::Delete => {
+                if let Some(name) = self.selected() {
+                    self.focused().remove_one(&name);
+                }
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+            }
+            KeyCode::Char(c) => self.search.push(c),
+            _ => {}
+        }
+
+        // Keep the selection in the filtered list.
+        let len = self.filtered().len();
+        let selected = self.list.selected().unwrap_or(0);
+        self.list.select(Some(selected.min(len.saturating_sub(1))));
+        true
+    }
+
+    fn side(&self, side: Side) -> (&AntennaSet, Option<Role>, &'static str) {
+        match side {
+            Side::From => (&self.from, self.from_role, DEFAULT_FROM),
+            Side::To => (&self.to, self.to_role, DEFAULT_TO),
+        }
+    }
+
+    fn title(&self, side: Side) -> String {
+        let name = match side {
+            Side::From => "From",
+            Side::To => "To",
+        };
+        match self.side(side).1 {
+            Some(Role::Relay) => format!("{} (relay)", name),
+            Some(Role::Direct) => format!("{} (direct)", name),
+            None => name.to_owned(),
+        }
+    }
+
+    /// Antennas of `side` left out by its relay role.
+    fn ignored(&self, side: Side) -> Vec<&str> {
+        let (set, role, _) = self.side(side);
+        if role != Some(Role::Relay) {
+            return Vec::new();
+        }
+        set.antennas
+            .iter()
+            .map(|(n, _)| n.as_str())
+            .filter(|n| {
+                self.antennas
+                    .get(n)
+                    .map_or(false, |a| !is_relay(self.antennas, a))
+            })
+            .collect()
+    }
+
+    /// Endpoint of `side`. The antennas left out by the role are removed here, because
+    /// the warnings of the builder would be written over the screen.
+    fn build(&self, side: Side) -> Result<Endpoint> {
+        let (set, role, default) = self.side(side);
+        let ignored = self.ignored(side);
+        let specs: Vec<String> = set
+            .antennas
+            .iter()
+            .filter(|(n, _)| !ignored.contains(&n.as_str()))
+            .map(|(n, c)| format!("{}:{}", c, n))
+            .collect();
+        if specs.is_empty() && !set.antennas.is_empty() && !self.lenient {
+            return Err(Error::msg("endpoint has no usable antenna"));
+        }
+
+        EndpointBuilder::new(self.antennas)
+            .modifiers(self.modifiers)
+            .lenient(self.lenient)
+            .role(role)
+            .build(specs.iter().map(String::as_str), default)
+    }
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(50),
+                Constraint::Min(5),
+                Constraint::Length(1),
+            ])
+            .split(f.size());
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(rows[0]);
+        let panes = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(top[1]);
+
+        self.draw_list(f, top[0]);
+
+        let from = self.build(Side::From);
+        let to = self.build(Side::To);
+        draw_endpoint(
+            f,
+            panes[0],
+            &self.title(Side::From),
+            self.focus == Side::From,
+            &from,
+            &self.ignored(Side::From),
+        );
+        draw_endpoint(
+            f,
+            panes[1],
+            &self.title(Side::To),
+            self.focus == Side::To,
+            &to,
+            &self.ignored(Side::To),
+        );
+
+        match (&from, &to) {
+            (Ok(from), Ok(to)) => self.draw_strengths(f, rows[1], from, to),
+            (Err(e), _) | (_, Err(e)) => {
+                let p = Paragraph::new(e.to_string())
+                    .block(Block::default().borders(Borders::ALL).title("Strength"));
+                f.render_widget(p, rows[1]);
+            }
+        }
+
+        f.render_widget(
+            Paragraph::new(KEYS).style(Style::default().fg(Color::DarkGray)),
+            rows[2],
+        );
+    }
+
+    fn draw_list<B: Backend>(&mut self, f: &mut Frame<B>, area: Rect) {
+        let items: Vec<ListItem> = self
+            .filtered()
+            .iter()
+            .map(|a| {
+                if a.aliases.is_empty() {
+                    ListItem::new(a.name.clone())
+                } else {
+                    ListItem::new(format!("{} ({})", a.name, a.aliases.join(", ")))
+                }
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Antennas: {}_", self.search)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_strengths<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        from: &Endpoint,
+        to: &Endpoint,
+    ) {
+        let max_distance = from.range_to(to).max_distance();
+        let rows: Vec<Row> = self
+            .sections
+            .strengths(from, to)
+            .into_iter()
+            .map(|s| {
+                Row::new(vec![
+                    s.section,
+                    format_strength(s.at_min),
+                    format_strength(s.at_max),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(rows)
+            .header(
+                Row::new(vec!["Section", "@Min", "@Max"])
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Strength (max distance {}m)",
+                MetricPrefix(max_distance)
+            )))
+            .widths(&[
+                Constraint::Length(27),
+                Constraint::Length(10),
+                Constraint::Length(10),
+            ]);
+        f.render_widget(table, area);
+    }
+}
+
+fn draw_endpoint<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: &str,
+    focused: bool,
+    endpoint: &Result<Endpoint>,
+    ignored: &[&str],
+) {
+    let mut lines = Vec::new();
+    match endpoint {
+        Ok(e) => {
+            lines.push(Spans::from(format!(
+                "{}, power {}",
+                e.endpoint_type(),
+                MetricPrefix(e.power())
+            )));
+            for (a, c) in e.antenna_counts() {
+                lines.push(Spans::from(format!("  {}x {}", c, a.name)));
+            }
+            for n in ignored {
+                lines.push(Spans::from(Span::styled(
+                    format!("  {} (not a relay, ignored)", n),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+        Err(err) => lines.push(Spans::from(Span::styled(
+            err.to_string(),
+            Style::default().fg(Color::Red),
+        ))),
+    }
+
+    let border = if focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    let p = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border)
+            .title(title.to_owned()),
+    );
+    f.render_widget(p, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::catalog::AntennaDef;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_str(bench: &mut Workbench, s: &str) {
+        for c in s.chars() {
+            assert!(bench.handle_key(key(KeyCode::Char(c))));
+        }
+    }
+
+    fn names(filtered: Vec<&Antenna>) -> Vec<&str> {
+        filtered.iter().map(|a| a.name.as_str()).collect()
+    }
+
+    #[test]
+    fn filters_by_name_and_alias() {
+        let mut catalog = Catalog::new();
+        catalog
+            .add(AntennaDef {
+                name: "HG-2 Reflectron".to_owned(),
+                aliases: vec!["Dish".to_owned()],
+                power: 1.0e9,
+                combinable: true,
+                combinable_exponent: 0.75,
+                relay: true,
+                ground_station: None,
+                mass: None,
+                cost: None,
+                packet_size: None,
+                packet_interval: None,
+                packet_cost: None,
+            })
+            .unwrap();
+        let mut bench = Workbench::new(&catalog);
+        assert_eq!(bench.filtered().len(), catalog.iter().count());
+
+        type_str(&mut bench, "hg-5");
+        assert_eq!(names(bench.filtered()), vec!["HG-5"]);
+
+        bench.search = "DISH".to_owned();
+        assert_eq!(names(bench.filtered()), vec!["HG-2 Reflectron"]);
+
+        bench.search = "no such antenna".to_owned();
+        assert!(bench.filtered().is_empty());
+    }
+
+    #[test]
+    fn edits_focused_endpoint() {
+        let catalog = Catalog::new();
+        let mut bench = Workbench::new(&catalog);
+
+        type_str(&mut bench, "RA-2");
+        assert!(bench.handle_key(key(KeyCode::Enter)));
+        assert!(bench.handle_key(key(KeyCode::Right)));
+        assert_eq!(bench.to.specs(), vec!["2:RA-2"]);
+        assert!(bench.from.antennas.is_empty());
+
+        assert!(bench.handle_key(key(KeyCode::Tab)));
+        assert_eq!(bench.focus, Side::From);
+        assert!(bench.handle_key(key(KeyCode::Enter)));
+        assert_eq!(bench.from.specs(), vec!["1:RA-2"]);
+        assert!(bench.handle_key(key(KeyCode::Left)));
+        assert!(bench.handle_key(key(KeyCode::Delete)));
+        assert!(bench.from.antennas.is_empty());
+        assert_eq!(bench.to.specs(), vec!["2:RA-2"]);
+
+        assert!(bench.handle_key(key(KeyCode::Backspace)));
+        assert_eq!(bench.search, "RA-");
+    }
+
+    #[test]
+    fn quits_on_esc_with_empty_search_and_ctrl_c() {
+        let catalog = Catalog::new();
+        let mut bench = Workbench::new(&catalog);
+
+        type_str(&mut bench, "q");
+        assert!(bench.handle_key(key(KeyCode::Esc)));
+        assert!(bench.search.is_empty());
+        assert!(!bench.handle_key(key(KeyCode::Esc)));
+
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert!(!bench.handle_key(ctrl_c));
+        assert!(bench.search.is_empty());
+    }
+
+    #[test]
+    fn clamps_selection() {
+        let catalog = Catalog::new();
+        let mut bench = Workbench::new(&catalog);
+        let len = bench.filtered().len();
+
+        assert!(bench.handle_key(key(KeyCode::Up)));
+        assert_eq!(bench.list.selected(), Some(0));
+        for _ in 0..len + 5 {
+            assert!(bench.handle_key(key(KeyCode::Down)));
+        }
+        assert_eq!(bench.list.selected(), Some(len - 1));
+
+        type_str(&mut bench, "hg-5");
+        assert_eq!(bench.list.selected(), Some(0));
+        assert_eq!(bench.selected().as_deref(), Some("HG-5"));
+
+        type_str(&mut bench, "x");
+        assert_eq!(bench.list.selected(), Some(0));
+        assert_eq!(bench.selected(), None);
+        assert!(bench.handle_key(key(KeyCode::Enter)));
+        assert!(bench.to.antennas.is_empty());
+    }
+
+    #[test]
+    fn relay_role_ignores_direct_antennas() {
+        let catalog = Catalog::new();
+        let mut bench = Workbench::new(&catalog);
+        bench.to_role = Some(Role::Relay);
+        bench.to.add("Communotron 16", 1);
+        bench.to.add("RA-2", 1);
+
+        assert_eq!(bench.ignored(Side::To), vec!["Communotron 16"]);
+        assert!(bench.ignored(Side::From).is_empty());
+        let to = bench.build(Side::To).unwrap();
+        let built: Vec<String> = to
+            .antenna_counts()
+            .into_iter()
+            .map(|(a, _)| a.name.clone())
+            .collect();
+        assert_eq!(built, vec!["RA-2"]);
+
+        bench.to.remove_all("RA-2");
+        assert!(bench.build(Side::To).is_err());
+        bench.lenient = true;
+        assert!(bench.build(Side::To).is_ok());
+    }
+}
@@ -0,0 +1,373 @@
+//! Tests of the report builder and the renderers of the library.
+
+use ksp_commnet_calculator_cli::catalog::Catalog;
+use ksp_commnet_calculator_cli::endpoint::{Modifiers, Role};
+use ksp_commnet_calculator_cli::geometry::Section;
+use ksp_commnet_calculator_cli::render::delimited::{self, Delimiter};
+use ksp_commnet_calculator_cli::render::{format_strength, json, markdown};
+use ksp_commnet_calculator_cli::report::{
+    parse_design, parse_science, Design, Report, ReportBuilder,
+};
+use ksp_commnet_calculator_cli::{DEFAULT_FROM, DEFAULT_TO};
+
+fn report(from: &[&str], to: &[&str]) -> Report {
+    let catalog = Catalog::new();
+    ReportBuilder::new(&catalog)
+        .build_specs(from.iter().copied(), to.iter().copied())
+        .unwrap()
+}
+
+#[test]
+fn defaults_empty_endpoints() {
+    let r = report(&[], &[]);
+    assert_eq!(r.from.antennas, vec![(DEFAULT_FROM.to_owned(), 1)]);
+    assert_eq!(r.to.antennas, vec![(DEFAULT_TO.to_owned(), 1)]);
+    assert!(r.from.ground_station);
+    assert!(!r.to.ground_station);
+}
+
+#[test]
+fn counts_antennas() {
+    let one = report(&["DSN Lv.3"], &["HG-5"]);
+    let two = report(&["DSN Lv.3"], &["2:HG-5"]);
+
+    assert_eq!(two.to.antennas, vec![("HG-5".to_owned(), 2)]);
+    assert!(two.to.power > one.to.power);
+    assert!(two.max_distance > one.max_distance);
+}
+
+#[test]
+fn rejects_unknown_antenna() {
+    let catalog = Catalog::new();
+    let err = ReportBuilder::new(&catalog)
+        .build_specs(vec![], vec!["No Such Antenna"])
+        .unwrap_err();
+    assert!(err.to_string().contains("No Such Antenna"));
+}
+
+#[test]
+fn sections_weaken_with_distance() {
+    let r = report(&["DSN Lv.3"], &["HG-5"]);
+    assert!(!r.sections.is_empty());
+    for s in &r.sections {
+        if let (Some(min), Some(max)) = (s.at_min, s.at_max) {
+            assert!(min >= max, "{}", s.section);
+        }
+    }
+}
+
+#[test]
+fn strength_at_distance() {
+    let catalog = Catalog::new();
+    let builder = ReportBuilder::new(&catalog);
+
+    let r = builder.build_specs(vec![], vec![]).unwrap();
+    assert!(r.at_distance.is_none());
+
+    let near = ReportBuilder::new(&catalog)
+        .distance(Some(0.0))
+        .build_specs(vec![], vec![])
+        .unwrap();
+    assert_eq!(near.at_distance.unwrap().strength, Some(1.0));
+
+    let far = ReportBuilder::new(&catalog)
+        .distance(Some(2.0 * r.max_distance))
+        .build_specs(vec![], vec![])
+        .unwrap();
+    assert_eq!(far.at_distance.unwrap().strength, None);
+}
+
+#[test]
+fn appends_extra_section() {
+    let catalog = Catalog::new();
+    let r = ReportBuilder::new(&catalog)
+        .section(Section {
+            name: "Here - There".to_owned(),
+            min: 0.0,
+            max: 1.0e30,
+        })
+        .build_specs(vec![], vec![])
+        .unwrap();
+
+    let last = r.sections.last().unwrap();
+    assert_eq!(last.section, "Here - There");
+    assert_eq!(last.at_min, Some(1.0));
+    assert_eq!(last.at_max, None);
+}
+
+#[test]
+fn applies_modifiers() {
+    let catalog = Catalog::new();
+    let modifiers = Modifiers {
+        range: 2.0,
+        dsn: 1.0,
+    };
+    let base = ReportBuilder::new(&catalog)
+        .build_specs(vec![], vec!["HG-5"])
+        .unwrap();
+    let boosted = ReportBuilder::new(&catalog)
+        .modifiers(modifiers)
+        .build_specs(vec![], vec!["HG-5"])
+        .unwrap();
+
+    assert_eq!(boosted.modifiers, modifiers);
+    assert!(boosted.max_distance > base.max_distance);
+}
+
+#[test]
+fn roles_and_lenient() {
+    let catalog = Catalog::new();
+
+    let direct = ReportBuilder::new(&catalog)
+        .build_specs(vec![], vec!["HG-5", "Communotron 16"])
+        .unwrap();
+    let relay = ReportBuilder::new(&catalog)
+        .to_role(Some(Role::Relay))
+        .build_specs(vec![], vec!["HG-5", "Communotron 16"])
+        .unwrap();
+    assert_eq!(relay.to.antennas, vec![("HG-5".to_owned(), 1)]);
+    assert!(relay.max_distance < direct.max_distance);
+
+    assert!(ReportBuilder::new(&catalog)
+        .build_specs(vec![], vec!["HG-5", "No Such Antenna"])
+        .is_err());
+    let lenient = ReportBuilder::new(&catalog)
+        .lenient(true)
+        .build_specs(vec![], vec!["HG-5", "No Such Antenna"])
+        .unwrap();
+    assert_eq!(lenient.to.antennas, vec![("HG-5".to_owned(), 1)]);
+}
+
+#[test]
+fn science_by_vessel() {
+    let catalog = Catalog::new();
+    let r = ReportBuilder::new(&catalog)
+        .science(Some(100.0))
+        .build_specs(vec!["DSN Lv.3"], vec!["HG-5"])
+        .unwrap();
+
+    let science = r.science.unwrap();
+    assert_eq!(science.antenna, "HG-5");
+    assert_eq!(science.sections.len(), r.sections.len());
+    assert!((science.charge - 50.0 * 18.0).abs() < 1e-9);
+}
+
+#[test]
+fn rejects_invalid_science() {
+    let catalog = Catalog::new();
+    assert!(ReportBuilder::new(&catalog)
+        .science(Some(-1.0))
+        .build_specs(vec![], vec![])
+        .is_err());
+}
+
+#[test]
+fn parses_science() {
+    assert_eq!(parse_science("12.5").unwrap(), 12.5);
+    assert_eq!(parse_science(" 100 ").unwrap(), 100.0);
+
+    for invalid in &["0", "-1", "NaN", "inf", "many"] {
+        let err = parse_science(invalid).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("science should be a positive number of Mits"),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn renders_science_columns() {
+    let catalog = Catalog::new();
+    let r = ReportBuilder::new(&catalog)
+        .science(Some(100.0))
+        .build_specs(vec!["DSN Lv.3"], vec!["HG-5"])
+        .unwrap();
+
+    let csv = delimited::render(&r, Delimiter::Comma, true);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "section,at_min,at_max,time_at_min,time_at_max,charge"
+    );
+    assert_eq!(lines.len(), r.sections.len() + 1);
+    for line in &lines[1..] {
+        assert_eq!(line.split(',').count(), 6, "{}", line);
+        assert!(line.ends_with(",900"), "{}", line);
+    }
+}
+
+#[test]
+fn renders_distance_row() {
+    let catalog = Catalog::new();
+    let r = ReportBuilder::new(&catalog)
+        .distance(Some(1.0e6))
+        .science(Some(100.0))
+        .build_specs(vec!["DSN Lv.3"], vec!["HG-5"])
+        .unwrap();
+    let strength = r.at_distance.unwrap().strength.unwrap();
+
+    let csv = delimited::render(&r, Delimiter::Comma, true);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "section,distance,at_min,at_max,at_distance,time_at_min,time_at_max,charge"
+    );
+    assert_eq!(lines.len(), r.sections.len() + 2);
+    for line in &lines[1..lines.len() - 1] {
+        let fields: Vec<&str> = line.split(',').collect();
+        assert_eq!(fields.len(), 8, "{}", line);
+        assert_eq!((fields[1], fields[4]), ("", ""), "{}", line);
+    }
+    assert_eq!(
+        *lines.last().unwrap(),
+        format!(",1000000,,,{},,,900", strength)
+    );
+
+    let csv = delimited::render_comparison(
+        &[Design {
+            name: "a".to_owned(),
+            report: r,
+        }],
+        Delimiter::Comma,
+        true,
+    );
+    assert!(csv.starts_with("section,distance,a_at_min,a_at_max,a_at_distance,a_time_at_min,"));
+    assert!(csv.ends_with(&format!(",1000000,,,{},,,900\n", strength)));
+    let d = designs(&["a=HG-5", "b=RA-2"]);
+    assert!(!delimited::render_comparison(&d, Delimiter::Comma, true).contains("distance"));
+}
+
+#[test]
+fn renders_report() {
+    let r = report(&["DSN Lv.3"], &["2:HG-5"]);
+
+    let md = markdown::render(&r);
+    assert!(md.contains(" Max distance: "));
+    assert!(md.contains("2x HG-5"));
+
+    let csv = delimited::render(&r, Delimiter::Comma, true);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("section,at_min,at_max"));
+    assert_eq!(lines.count(), r.sections.len());
+
+    let value: serde_json::Value = serde_json::from_str(&json::render(&r).unwrap()).unwrap();
+    assert_eq!(value["version"], 1);
+    assert_eq!(value["to"]["antennas"][0]["count"], 2);
+}
+
+fn designs(designs: &[&str]) -> Vec<Design> {
+    let catalog = Catalog::new();
+    let builder = ReportBuilder::new(&catalog);
+    let from = builder.build_from(vec!["DSN Lv.3"]).unwrap();
+    builder
+        .build_designs(&from, designs.iter().copied())
+        .unwrap()
+}
+
+#[test]
+fn parses_designs() {
+    assert_eq!(
+        parse_design(" probeA = 2:HG-5, RA-2 ").unwrap(),
+        ("probeA", "2:HG-5, RA-2")
+    );
+    assert_eq!(parse_design("a=b=c").unwrap(), ("a", "b=c"));
+
+    for invalid in &["probeA", "=HG-5", " =HG-5", "probeA=", "probeA= "] {
+        let err = parse_design(invalid).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("design should be like NAME=ANTENNAS"),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn builds_designs() {
+    let d = designs(&["one=HG-5", "two=2:HG-5, RA-2"]);
+    assert_eq!(d.len(), 2);
+    assert_eq!(d[0].name, "one");
+    let antennas = &d[1].report.to.antennas;
+    assert!(antennas.contains(&("HG-5".to_owned(), 2)));
+    assert!(antennas.contains(&("RA-2".to_owned(), 1)));
+    assert!(d[1].report.max_distance > d[0].report.max_distance);
+
+    let catalog = Catalog::new();
+    let builder = ReportBuilder::new(&catalog);
+    let from = builder.build_from(vec![]).unwrap();
+    let err = builder
+        .build_designs(&from, vec!["a=HG-5", "b=RA-2", "a=RA-2"])
+        .unwrap_err();
+    assert_eq!(err.to_string(), "duplicate design 'a'");
+}
+
+#[test]
+fn aligns_comparison_with_long_names() {
+    let d = designs(&["a-very-long-design-name=HG-5", "b=RA-2"]);
+    let md = markdown::render_comparison(&d);
+
+    let table: Vec<&str> = md.lines().filter(|l| l.starts_with(" |")).collect();
+    assert_eq!(table.len(), d[0].report.sections.len() + 2);
+    assert!(table[0].contains(" a-very-long-design-name @Min |"));
+    assert!(table[0].contains(" b @Min |"));
+    let width = table[0].chars().count();
+    for line in &table {
+        assert_eq!(line.chars().count(), width, "{}", line);
+        assert_eq!(line.matches('|').count(), 6, "{}", line);
+    }
+}
+
+#[test]
+fn quotes_comparison_header() {
+    let d = designs(&["a,b=HG-5", "c=RA-2"]);
+    let csv = delimited::render_comparison(&d, Delimiter::Comma, true);
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("section,\"a,b_at_min\",\"a,b_at_max\",c_at_min,c_at_max")
+    );
+    assert_eq!(lines.count(), d[0].report.sections.len());
+
+    let tsv = delimited::render_comparison(&d, Delimiter::Tab, false);
+    assert_eq!(tsv.lines().count(), d[0].report.sections.len());
+}
+
+#[test]
+fn renders_comparison_json() {
+    let d = designs(&["one=HG-5", "two=2:HG-5"]);
+    let value: serde_json::Value =
+        serde_json::from_str(&json::render_comparison(&d).unwrap()).unwrap();
+
+    assert_eq!(value["version"], 1);
+    assert!(value.get("to").is_none());
+    let list = value["designs"].as_array().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0]["name"], "one");
+    assert_eq!(list[1]["name"], "two");
+    assert_eq!(list[0]["from"]["antennas"][0]["name"], "DSN Lv.3");
+    assert_eq!(list[1]["to"]["antennas"][0]["count"], 2);
+    assert_eq!(
+        list[1]["sections"].as_array().unwrap().len(),
+        d[1].report.sections.len()
+    );
+    assert!(list[0].get("at_distance").is_none());
+    assert!(list[0].get("science").is_none());
+}
+
+#[test]
+fn quotes_delimited_fields() {
+    assert_eq!(
+        delimited::record(&["a,b", "say \"hi\"", "c"], Delimiter::Comma),
+        "\"a,b\",\"say \"\"hi\"\"\",c"
+    );
+    assert_eq!(delimited::record(&["a,b", "c"], Delimiter::Tab), "a,b\tc");
+}
+
+#[test]
+fn formats_strength() {
+    assert_eq!(format_strength(Some(0.5)), "50.0 %");
+    assert_eq!(format_strength(None), "NA");
+}
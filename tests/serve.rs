@@ -0,0 +1,146 @@
+//! Integration tests of `serve` with a plain HTTP client on localhost.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+use serde_json::{json, Value};
+
+/// Server on a free port, killed on drop.
+struct TestServer {
+    child: Child,
+    addr: String,
+}
+
+impl TestServer {
+    fn start() -> TestServer {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_ksp-commnet-calculator-cli"))
+            .args(&["serve", "--port", "0"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start the server");
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.as_mut().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("Listening on http://")
+            .unwrap_or_else(|| panic!("unexpected output: {}", line))
+            .to_owned();
+
+        TestServer { child, addr }
+    }
+
+    /// Sends a request, and returns the status code and the JSON body.
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            self.addr,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| panic!("invalid response: {}", response));
+        let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+        (status, serde_json::from_str(body).unwrap())
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn antennas_lists_stock_antennas() {
+    let server = TestServer::start();
+    let (status, body) = server.request("GET", "/antennas", "");
+
+    assert_eq!(status, 200);
+    let list = body.as_array().unwrap();
+    assert!(list.iter().any(|a| a["name"] == "DSN Lv.3"));
+    assert!(list.iter().any(|a| a["name"] == "HG-5"));
+}
+
+#[test]
+fn range_returns_report() {
+    let server = TestServer::start();
+    let request = json!({ "from": ["DSN Lv.3"], "to": ["2:HG-5"] });
+    let (status, body) = server.request("POST", "/range", &request.to_string());
+
+    assert_eq!(status, 200);
+    assert_eq!(body["version"], 1);
+    assert_eq!(body["to"]["antennas"][0]["name"], "HG-5");
+    assert_eq!(body["to"]["antennas"][0]["count"], 2);
+    assert!(body["max_distance"].as_f64().unwrap() > 0.0);
+    assert!(!body["sections"].as_array().unwrap().is_empty());
+    assert!(body.get("at_distance").is_none());
+}
+
+#[test]
+fn range_defaults_endpoints() {
+    let server = TestServer::start();
+    let (status, body) = server.request("POST", "/range", "{}");
+
+    assert_eq!(status, 200);
+    assert_eq!(body["from"]["antennas"][0]["name"], "DSN Lv.3");
+    assert_eq!(body["to"]["antennas"][0]["name"], "Command Module");
+}
+
+#[test]
+fn range_with_distance() {
+    let server = TestServer::start();
+    let request = json!({ "to": ["HG-5"], "distance": 1.0e6 });
+    let (status, body) = server.request("POST", "/range", &request.to_string());
+
+    assert_eq!(status, 200);
+    assert_eq!(body["at_distance"]["distance"], 1.0e6);
+    let strength = body["at_distance"]["strength"].as_f64().unwrap();
+    assert!(strength > 0.0 && strength <= 1.0);
+}
+
+#[test]
+fn range_rejects_unknown_antenna() {
+    let server = TestServer::start();
+    let request = json!({ "to": ["No Such Antenna"] });
+    let (status, body) = server.request("POST", "/range", &request.to_string());
+
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("No Such Antenna"));
+}
+
+#[test]
+fn range_rejects_invalid_body() {
+    let server = TestServer::start();
+    let (status, body) = server.request("POST", "/range", "not json");
+
+    assert_eq!(status, 400);
+    assert!(body["error"].is_string());
+}
+
+#[test]
+fn unknown_path_and_method() {
+    let server = TestServer::start();
+
+    let (status, _) = server.request("GET", "/nothing", "");
+    assert_eq!(status, 404);
+
+    let (status, _) = server.request("GET", "/range", "");
+    assert_eq!(status, 405);
+}